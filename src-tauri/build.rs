@@ -2,5 +2,11 @@ fn main() {
     // Check for migrations.
     println!("cargo:rerun-if-changed=migrations");
 
+    // Expose the target triple for the build metadata.
+    println!(
+        "cargo:rustc-env=TARGET_TRIPLE={}",
+        std::env::var("TARGET").unwrap()
+    );
+
     tauri_build::build()
 }
@@ -0,0 +1,2 @@
+-- Marks profiles which were imported from a shared file.
+ALTER TABLE `profiles` ADD `imported` BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,3 @@
+-- Adds checksums to tools and mods, verified before they are used.
+ALTER TABLE `tools` ADD `sha256` TEXT;
+ALTER TABLE `mods` ADD `sha256` TEXT;
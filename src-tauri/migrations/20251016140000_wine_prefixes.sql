@@ -0,0 +1,2 @@
+-- The Wine prefix used to run the game on Linux.
+ALTER TABLE `profiles` ADD `wine_prefix` TEXT;
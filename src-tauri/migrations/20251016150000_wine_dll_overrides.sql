@@ -0,0 +1,3 @@
+-- The Wine DLL overrides used to run the game on Linux.
+-- These are stored in the `WINEDLLOVERRIDES` format.
+ALTER TABLE `profiles` ADD `wine_dll_overrides` TEXT NOT NULL DEFAULT '';
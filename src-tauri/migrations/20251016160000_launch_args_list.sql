@@ -0,0 +1,10 @@
+-- Launch arguments are now stored as a JSON list.
+-- Rows with a single simple argument are converted here,
+-- the rest are split when they are loaded and stored as a list on the next save.
+UPDATE `profiles` SET `launch_args` = '[]' WHERE TRIM(`launch_args`) = '';
+UPDATE `profiles` SET `launch_args` = JSON_ARRAY(TRIM(`launch_args`))
+WHERE TRIM(`launch_args`) != ''
+  AND INSTR(TRIM(`launch_args`), ' ') = 0
+  AND INSTR(`launch_args`, '"') = 0
+  AND INSTR(`launch_args`, CHAR(9)) = 0
+  AND SUBSTR(TRIM(`launch_args`), 1, 1) != '[';
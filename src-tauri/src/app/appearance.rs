@@ -1,12 +1,263 @@
+use crate::config::{save_config, Config};
+use crate::events::Event;
+use crate::utils::MaybeError;
+use crate::{events, utils};
+use anyhow::anyhow;
 use lazy_static::lazy_static;
-use log::debug;
+use log::{debug, warn};
 use regex::Regex;
-use serde_json::Value;
+use serde::{Deserialize, Serialize};
 use std::fs;
-use tauri::{AppHandle, Manager};
+use std::path::PathBuf;
+use std::time::Duration;
+use tauri::AppHandle;
 
 lazy_static! {
     static ref FILE_NAME_REGEX: Regex = Regex::new(r"https:\/\/.*\/(.*_.*\.webp)").unwrap();
+    static ref PRESETS: Vec<Preset> =
+        serde_yml::from_str(include_str!("../../../resources/appearance/presets.yml")).unwrap();
+}
+
+/// Where the launcher background comes from.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+pub enum BackgroundSource {
+    /// The latest background from the game's API.
+    Api,
+
+    /// The default splash screen bundled with the launcher.
+    Splash,
+}
+
+/// An appearance preset.
+///
+/// Presets are bundled with the launcher in `resources/appearance/presets.yml`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Preset {
+    /// The name used to select the preset.
+    pub name: String,
+
+    /// Where the launcher background comes from.
+    pub background: BackgroundSource,
+
+    /// The accent color, in hex.
+    ///
+    /// If this is `None`, it is derived from the background.
+    pub accent_color: Option<String>,
+
+    /// Whether to show the splash screen while the launcher starts.
+    pub show_splash: bool,
+}
+
+/// Returns the appearance preset selected in the configuration.
+///
+/// If the selected preset doesn't exist, the first preset is used.
+pub fn current_preset() -> Preset {
+    let name = Config::launcher().appearance_preset;
+
+    PRESETS
+        .iter()
+        .find(|preset| preset.name == name)
+        .unwrap_or(&PRESETS[0])
+        .clone()
+}
+
+/// Lists all available appearance presets.
+#[tauri::command]
+pub fn appearance__list_presets() -> Vec<Preset> {
+    PRESETS.clone()
+}
+
+/// Sets the appearance preset by its name.
+///
+/// This saves the preset to the configuration and notifies the frontend.
+#[tauri::command]
+pub fn appearance__set_preset(app_handle: AppHandle, name: String) -> MaybeError<()> {
+    // Check if the preset exists.
+    let Some(preset) = PRESETS.iter().find(|preset| preset.name == name) else {
+        return Err("launcher.error.appearance.bad-preset");
+    };
+
+    // Write the preset to the configuration.
+    {
+        let mut config = Config::get();
+        config.launcher.appearance_preset = name;
+
+        if let Err(error) = save_config(&config) {
+            warn!("Failed to save the configuration: {}", error);
+            return Err("backend.config.save-failed");
+        }
+    }
+
+    // Notify the frontend of the change.
+    events::emit_event(&app_handle, Event::AppearanceChanged(preset.clone()));
+
+    Ok(())
+}
+
+/// How long the basic game information is cached for.
+const GAME_INFO_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// A response from the basic game information API.
+#[derive(Deserialize, Debug)]
+struct ApiResponse<T> {
+    data: T,
+}
+
+/// The `data` of the basic game information API.
+#[derive(Deserialize, Debug)]
+struct GameInfoList {
+    game_info_list: Vec<GameEntry>,
+}
+
+/// The information of a single game.
+#[derive(Deserialize, Debug)]
+struct GameEntry {
+    game: GameId,
+    #[serde(default)]
+    display: Option<GameDisplay>,
+    #[serde(default)]
+    backgrounds: Vec<Background>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GameId {
+    id: String,
+}
+
+/// The display text of a game, if the API provides it.
+#[derive(Deserialize, Debug)]
+struct GameDisplay {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    subtitle: Option<String>,
+}
+
+/// A background, and its accompanying event icon.
+#[derive(Deserialize, Debug)]
+struct Background {
+    background: Image,
+    #[serde(default)]
+    icon: Option<Image>,
+}
+
+/// An image, with an optional link to an announcement.
+#[derive(Deserialize, Debug)]
+struct Image {
+    url: String,
+    #[serde(default)]
+    link: String,
+}
+
+/// The current game information shown by the launcher.
+#[derive(Serialize, Debug)]
+pub struct GameInfo {
+    /// The title of the game.
+    title: Option<String>,
+
+    /// The subtitle of the game.
+    ///
+    /// This usually describes the current version.
+    subtitle: Option<String>,
+
+    /// Links to the current event announcements.
+    announcements: Vec<String>,
+}
+
+/// Fetches the basic game information for this game.
+///
+/// Each configured endpoint is tried in order until one succeeds.\
+/// The response is cached for `GAME_INFO_TTL`.
+async fn fetch_game_entry(cache_dir: &PathBuf) -> anyhow::Result<GameEntry> {
+    let launcher = Config::launcher();
+    let file = cache_dir.join("game-info.json");
+
+    let endpoints =
+        std::iter::once(&launcher.game_info_url).chain(launcher.game_info_fallback_urls.iter());
+
+    let mut last_error = anyhow!("no game information endpoints are configured");
+    for endpoint in endpoints {
+        match fetch_from_endpoint(endpoint, &file, &launcher.game_id).await {
+            Ok(entry) => {
+                debug!("Fetched the basic game information from '{}'.", endpoint);
+                return Ok(entry);
+            }
+            Err(error) => {
+                warn!(
+                    "Failed to fetch the basic game information from '{}': {}",
+                    endpoint, error
+                );
+
+                // Discard the cached response, so the next endpoint is used.
+                let _ = fs::remove_file(&file);
+                last_error = error;
+            }
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Fetches the basic game information for this game from a single endpoint.
+async fn fetch_from_endpoint(
+    endpoint: &str,
+    file: &PathBuf,
+    game_id: &str,
+) -> anyhow::Result<GameEntry> {
+    // Download the basic game information.
+    utils::cached_download(endpoint, file, Some(GAME_INFO_TTL), |_, _| {}).await?;
+
+    // Parse the game information list.
+    let response: ApiResponse<GameInfoList> = serde_json::from_slice(&fs::read(file)?)?;
+    response
+        .data
+        .game_info_list
+        .into_iter()
+        .find(|entry| entry.game.id == game_id)
+        .ok_or_else(|| anyhow!("the game is missing from the game information list"))
+}
+
+/// Fetches the current game information for the launcher.
+///
+/// This includes the title, subtitle, and links to any current event announcements.
+#[tauri::command]
+pub async fn appearance__game_info() -> MaybeError<GameInfo> {
+    // Resolve the cache directory.
+    let Ok(app_data_dir) = utils::app_data_dir() else {
+        return Err("Failed to resolve app data directory.");
+    };
+    let cache_dir = app_data_dir.join("cache");
+
+    let entry = match fetch_game_entry(&cache_dir).await {
+        Ok(entry) => entry,
+        Err(error) => {
+            warn!("Failed to fetch the basic game information: {}", error);
+            return Err("launcher.error.appearance.game-info");
+        }
+    };
+
+    // Collect the announcement links from the backgrounds.
+    let announcements = entry
+        .backgrounds
+        .iter()
+        .flat_map(|background| [Some(&background.background), background.icon.as_ref()])
+        .flatten()
+        .map(|image| image.link.clone())
+        .filter(|link| !link.is_empty())
+        .collect();
+
+    let (title, subtitle) = match entry.display {
+        Some(display) => (display.name, display.subtitle),
+        None => (None, None),
+    };
+
+    Ok(GameInfo {
+        title,
+        subtitle,
+        announcements,
+    })
 }
 
 /// This is the default background/splashscreen used by the launcher.
@@ -18,20 +269,41 @@ const DEFAULT_SPLASH: &[u8] = include_bytes!("../../../resources/assets/default_
 
 /// Saves the default splash screen to the cache directory.
 ///
-/// This only occurs if the default splash screen is not already present.
+/// This only occurs if the default splash screen is missing or corrupted,\
+/// unless `force` is set, in which case it is always rewritten.
 #[tauri::command]
-pub fn appearance__default_splash(app_handle: AppHandle) -> Result<String, &'static str> {
+pub fn appearance__default_splash(force: Option<bool>) -> Result<String, &'static str> {
+    default_splash(force.unwrap_or(false))
+}
+
+/// Checks if the file is an intact copy of the default splash screen.
+fn is_intact_splash(file: &PathBuf) -> bool {
+    let size_matches = fs::metadata(file)
+        .map(|metadata| metadata.len() == DEFAULT_SPLASH.len() as u64)
+        .unwrap_or(false);
+
+    size_matches && utils::read_file(file).is_ok_and(|data| data == DEFAULT_SPLASH)
+}
+
+/// Saves the default splash screen to the cache directory.
+///
+/// See `appearance__default_splash` for when the file is written.
+fn default_splash(force: bool) -> Result<String, &'static str> {
     // Resolve the cache directory.
-    let Ok(app_data_dir) = app_handle.path().app_data_dir() else {
+    let Ok(app_data_dir) = utils::app_data_dir() else {
         return Err("Failed to resolve app data directory.");
     };
     let cache_dir = app_data_dir.join("cache");
 
     // Write the default splash screen to the cache directory.
     let file = cache_dir.join("default.webp");
-    if !file.exists() {
+    if force || !is_intact_splash(&file) {
+        if let Err(error) = fs::write(&file, DEFAULT_SPLASH) {
+            warn!("Failed to write the default splash screen: {}", error);
+            return Err("Failed to write the default splash screen.");
+        }
+
         debug!("Wrote default splash screen to cache directory.");
-        fs::write(&file, DEFAULT_SPLASH).unwrap();
     }
 
     // Return the asset path to the default splash screen.
@@ -43,52 +315,38 @@ pub fn appearance__default_splash(app_handle: AppHandle) -> Result<String, &'sta
 
 /// Fetches the background for the launcher.
 ///
-/// This will load it from the cache, if applicable.
+/// This will load it from the cache, if applicable.\
+/// If the appearance preset uses the default splash screen, that is returned instead.
 #[tauri::command]
 pub async fn appearance__background(app_handle: AppHandle) -> Result<String, &'static str> {
+    // If the preset doesn't use the API, use the default background.
+    if current_preset().background == BackgroundSource::Splash {
+        return default_splash(false);
+    }
+
     // Resolve the cache directory.
-    let Ok(app_data_dir) = app_handle.path().app_data_dir() else {
+    let Ok(app_data_dir) = utils::app_data_dir() else {
         return Err("Failed to resolve app data directory.");
     };
     let cache_dir = app_data_dir.join("cache");
 
     // Fetch the basic game information from the API.
-    let Ok(response) = reqwest::get(dotenv!("BASIC_GAME_INFO_URL")).await else {
-        return Err("Failed to perform request for basic game information.");
-    };
-
     // If the request failed, try to save/use the default background.
-    if !response.status().is_success() {
-        return appearance__default_splash(app_handle);
-    }
-
-    // Get the background URL from the response.
-    // Step 1. Parse the response as JSON data.
-    let Ok(text) = response.text().await else {
-        return Err("Failed to read response text.");
-    };
-    let Ok(response) = serde_json::from_str::<Value>(&text) else {
-        return Err("Failed to parse response as JSON data.");
-    };
-
-    // Step 2. Extract the background URL from the JSON data.
-    let data = &response["data"];
-    let Some(data) = data["game_info_list"].as_array() else {
-        return Err("Failed to extract game information list.");
+    let entry = match fetch_game_entry(&cache_dir).await {
+        Ok(entry) => entry,
+        Err(error) => {
+            warn!("Failed to fetch the basic game information: {}", error);
+            return default_splash(false);
+        }
     };
 
-    let Some(game) = data.iter().find(|v| v["game"]["id"] == dotenv!("GAME_ID")) else {
-        return Err("Failed to find game information.");
-    };
-
-    let Some(backgrounds) = game["backgrounds"].as_array() else {
+    // Get the background URL from the game information.
+    let Some(background) = entry.backgrounds.first() else {
         return Err("Failed to extract backgrounds.");
     };
-    let Some(url) = backgrounds[0]["background"]["url"].as_str() else {
-        return Err("Failed to extract background URL.");
-    };
+    let url = background.background.url.as_str();
 
-    // Step 3. Extract the file name from the URL & query for data.
+    // Extract the file name from the URL & query for data.
     let file_name = FILE_NAME_REGEX
         .captures(url)
         .and_then(|c| c.get(1))
@@ -96,16 +354,19 @@ pub async fn appearance__background(app_handle: AppHandle) -> Result<String, &'s
         .unwrap_or("background.webp");
     let file = cache_dir.join(file_name);
 
-    // If the file doesn't exist, download it and save it.
-    if !file.exists() {
-        let Ok(response) = reqwest::get(url).await else {
-            return Err("Failed to perform request for background image.");
-        };
-        let Ok(bytes) = response.bytes().await else {
-            return Err("Failed to read response bytes.");
+    // If the file isn't cached, download it and save it.
+    let on_progress = |received, total| {
+        let event = Event::DownloadProgress {
+            file: file_name.to_string(),
+            received,
+            total,
         };
+        events::emit_event(&app_handle, event);
+    };
 
-        fs::write(&file, bytes).unwrap();
+    if let Err(error) = utils::cached_download(url, &file, None, on_progress).await {
+        warn!("Failed to download background image: {}", error);
+        return Err("Failed to perform request for background image.");
     }
 
     match file.to_str() {
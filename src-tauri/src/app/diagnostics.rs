@@ -0,0 +1,118 @@
+use crate::app::game::{self, LaunchCommand};
+use crate::app::{self, AppInfo};
+use crate::capabilities::sniffer;
+use crate::state::SelectedProfile;
+use crate::system;
+use crate::system::OpenResult;
+use crate::utils::{MaybeError, Recover};
+use log::warn;
+use serde::Serialize;
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager, State};
+
+/// The Npcap download page.
+const NPCAP_DOWNLOAD_URL: &str = "https://npcap.com/#download";
+
+/// The path of the Npcap installer, if it is bundled with the launcher.
+const NPCAP_INSTALLER: &str = "../resources/npcap/npcap-installer.exe";
+
+/// Information for troubleshooting the launcher.
+#[derive(Serialize, Debug)]
+pub struct Diagnostics {
+    /// The version and build metadata of the application.
+    pub app: AppInfo,
+
+    /// Why packets can't be captured, if they can't be.
+    pub capture_error: Option<&'static str>,
+
+    /// The command the selected profile's game is opened with.
+    pub launch_command: Option<LaunchCommand>,
+
+    /// Why the launch command can't be built, if it can't be.
+    pub launch_error: Option<&'static str>,
+}
+
+/// Collects information for troubleshooting the launcher.
+///
+/// This can be shared with support when something doesn't work.
+#[tauri::command]
+pub fn diagnostics__run(profile: State<SelectedProfile>) -> Diagnostics {
+    let launch_command = profile
+        .0
+        .lock()
+        .recover()
+        .as_ref()
+        .map(game::launch_command)
+        .transpose();
+    let (launch_command, launch_error) = match launch_command {
+        Ok(command) => (command, None),
+        Err(error) => (None, Some(error)),
+    };
+
+    Diagnostics {
+        app: app::app_info(),
+        capture_error: sniffer::check_capture().err(),
+        launch_command,
+        launch_error,
+    }
+}
+
+/// Helps the user install the packet capture library.
+///
+/// # On Windows
+///
+/// This runs the bundled Npcap installer, if it is shipped with the launcher.\
+/// Otherwise, this opens the Npcap download page.
+///
+/// # On Linux/macOS
+///
+/// This returns the package manager command to install libpcap.
+#[tauri::command]
+pub fn diagnostics__install_pcap(app_handle: AppHandle) -> MaybeError<Option<String>> {
+    if cfg!(windows) {
+        // Run the bundled installer, if it exists.
+        if let Ok(installer) = app_handle
+            .path()
+            .resolve(NPCAP_INSTALLER, BaseDirectory::Resource)
+        {
+            if installer.exists() {
+                let installer = installer.to_string_lossy();
+                if let Ok(OpenResult::Success) = system::open_executable(installer, None) {
+                    return Ok(None);
+                }
+            }
+        }
+
+        // Otherwise, open the download page.
+        if let Err(error) = open::that(NPCAP_DOWNLOAD_URL) {
+            warn!("Failed to open the Npcap download page: {}", error);
+            return Err("diagnostics.error.pcap-install");
+        }
+
+        return Ok(None);
+    }
+
+    Ok(Some(pcap_install_command().to_string()))
+}
+
+/// Returns the command to install libpcap with the system's package manager.
+fn pcap_install_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        return "brew install libpcap";
+    }
+
+    // Find the first package manager which is installed.
+    let package_managers = [
+        ("apt-get", "sudo apt-get install libpcap0.8"),
+        ("dnf", "sudo dnf install libpcap"),
+        ("pacman", "sudo pacman -S libpcap"),
+        ("zypper", "sudo zypper install libpcap1"),
+    ];
+
+    let paths = std::env::var_os("PATH").unwrap_or_default();
+    package_managers
+        .iter()
+        .find(|(binary, _)| std::env::split_paths(&paths).any(|dir| dir.join(binary).exists()))
+        .map(|(_, command)| *command)
+        .unwrap_or("sudo apt-get install libpcap0.8")
+}
@@ -1,20 +1,27 @@
-use crate::config::Config;
-use crate::utils::MaybeError;
-use crate::{database, system, utils, GLOBAL_STATE};
+use crate::config::{Config, Game};
+use crate::events::Event;
+use crate::utils::{HashAlgorithm, MaybeError, Recover, Translate};
+use crate::{database, events, system, utils, GLOBAL_STATE};
 use anyhow::{anyhow, Result};
 use clap::ArgMatches;
 use lazy_static::lazy_static;
 use log::warn;
 use regex::Regex;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::Error;
-use std::path::PathBuf;
-use std::sync::MutexGuard;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(windows)]
+use std::sync::Mutex;
 use std::thread::sleep;
-use std::time::Duration;
-use tauri::State;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, State};
+use tauri_plugin_dialog::DialogExt;
 use tokio::sync::{watch, watch::{Sender as WatchSender, Receiver as WatchReceiver}, RwLock};
 
+use crate::app::{runners, sniffer};
+use crate::state;
 use crate::state::SelectedProfile;
 #[cfg(windows)]
 use crate::{sys_str, system::AsCString};
@@ -28,6 +35,18 @@ lazy_static! {
     static ref GAME_STATUS: (WatchSender<bool>, WatchReceiver<bool>) = watch::channel(false);
 }
 
+/// Crashes within this time of the game opening are counted as launch crashes.
+const LAUNCH_CRASH_WINDOW: Duration = Duration::from_secs(60);
+
+/// This value holds whether the game is currently being launched.
+static GAME_LAUNCHING: AtomicBool = AtomicBool::new(false);
+
+/// The raw handle of the launched game process.
+///
+/// This is kept open so the exit code can be read once the game closes.
+#[cfg(windows)]
+static GAME_PROCESS: Mutex<Option<usize>> = Mutex::new(None);
+
 /// A game launch profile.
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Profile {
@@ -37,7 +56,30 @@ pub struct Profile {
     pub version: Version,
     pub tools: Vec<Tool>,
     pub mods: Vec<Mod>,
-    pub launch_args: String,
+
+    /// The arguments passed to the game, in order.
+    ///
+    /// Older profiles store these as a single command line, which is split when read.
+    #[serde(deserialize_with = "deserialize_launch_args")]
+    pub launch_args: Vec<String>,
+
+    /// Whether the profile was imported from a shared file.
+    ///
+    /// Tools of imported profiles can only be loaded from the allowed roots.
+    #[serde(default)]
+    pub imported: bool,
+
+    /// The Wine prefix used to run the game on Linux.
+    ///
+    /// If unset, Wine's default prefix is used.
+    #[serde(default)]
+    pub wine_prefix: Option<String>,
+
+    /// The Wine DLL overrides used to run the game on Linux.
+    ///
+    /// This maps DLL names to their load order, such as `n,b`.
+    #[serde(default)]
+    pub wine_dll_overrides: BTreeMap<String, String>,
 }
 
 impl Profile {
@@ -62,17 +104,46 @@ impl Profile {
             .collect::<Vec<String>>()
             .join(",");
 
+        let launch_args = serde_json::to_string(&self.launch_args)?;
+        let dll_overrides = runners::format_dll_overrides(&self.wine_dll_overrides);
+
         sqlx::query!(
-            r#"INSERT INTO `profiles` (`id`, `name`, `icon`, `version`, `tools`, `mods`, `launch_args`) VALUES
-            ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT(`id`) DO UPDATE SET
-            `name` = $2, `icon` = $3, `version` = $4, `tools` = $5, `mods` = $6, `launch_args` = $7"#,
-            self.id, self.name, self.icon, self.version.version, tools, mods, self.launch_args
+            r#"INSERT INTO `profiles` (`id`, `name`, `icon`, `version`, `tools`, `mods`, `launch_args`, `imported`, `wine_prefix`, `wine_dll_overrides`) VALUES
+            ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10) ON CONFLICT(`id`) DO UPDATE SET
+            `name` = $2, `icon` = $3, `version` = $4, `tools` = $5, `mods` = $6, `launch_args` = $7, `imported` = $8, `wine_prefix` = $9, `wine_dll_overrides` = $10"#,
+            self.id, self.name, self.icon, self.version.version, tools, mods, launch_args, self.imported, self.wine_prefix, dll_overrides
         ).execute(&pool).await?;
 
         Ok(())
     }
 }
 
+/// Parses the launch arguments stored in the database.
+///
+/// These are stored as a JSON list, but older rows store a single command line.
+fn parse_launch_args(launch_args: &str) -> Vec<String> {
+    serde_json::from_str(launch_args).unwrap_or_else(|_| utils::split_args(launch_args))
+}
+
+/// Deserializes the launch arguments of a profile.
+///
+/// Both a list of arguments and a single command line are accepted.
+fn deserialize_launch_args<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum LaunchArgs {
+        List(Vec<String>),
+        CommandLine(String),
+    }
+
+    Ok(match LaunchArgs::deserialize(deserializer)? {
+        LaunchArgs::List(args) => args,
+        LaunchArgs::CommandLine(command_line) => utils::split_args(&command_line),
+    })
+}
+
 /// A game 'modification'.
 ///
 /// This links to `Modification`.
@@ -82,6 +153,36 @@ pub struct Tool {
     pub name: String,
     pub icon: String,
     pub path: String,
+
+    /// The SHA-256 hash of the tool's file.
+    ///
+    /// If set, this is verified before the tool is used.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl Tool {
+    /// Saves the tool to the database.
+    ///
+    /// If it already exists, it updates the values.
+    pub async fn save(&self) -> Result<()> {
+        let pool = database::get_pool();
+
+        sqlx::query!(
+            r#"INSERT INTO `tools` (`id`, `name`, `icon`, `path`, `sha256`) VALUES
+            ($1, $2, $3, $4, $5) ON CONFLICT(`id`) DO UPDATE SET
+            `name` = $2, `icon` = $3, `path` = $4, `sha256` = $5"#,
+            self.id,
+            self.name,
+            self.icon,
+            self.path,
+            self.sha256
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
 }
 
 /// A game modification.
@@ -95,6 +196,38 @@ pub struct Mod {
     pub path: String,
     pub version: String,
     pub tool: Tool,
+
+    /// The SHA-256 hash of the mod's file.
+    ///
+    /// If set, this is verified before the mod is used.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+impl Mod {
+    /// Saves the mod to the database.
+    ///
+    /// If it already exists, it updates the values.
+    pub async fn save(&self) -> Result<()> {
+        let pool = database::get_pool();
+
+        sqlx::query!(
+            r#"INSERT INTO `mods` (`id`, `name`, `icon`, `path`, `version`, `tool`, `sha256`) VALUES
+            ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT(`id`) DO UPDATE SET
+            `name` = $2, `icon` = $3, `path` = $4, `version` = $5, `tool` = $6, `sha256` = $7"#,
+            self.id,
+            self.name,
+            self.icon,
+            self.path,
+            self.version,
+            self.tool.id,
+            self.sha256
+        )
+        .execute(&pool)
+        .await?;
+
+        Ok(())
+    }
 }
 
 /// A game version.
@@ -102,9 +235,36 @@ pub struct Mod {
 pub struct Version {
     pub version: String,
     pub path: String,
+
+    /// The region of the game, such as `OS` (global) or `CN` (China).
+    ///
+    /// This is derived from the version string.
+    #[serde(default)]
+    pub region: String,
+
+    /// The release branch of the game, such as `REL` (release) or `CB` (beta).
+    ///
+    /// This is derived from the version string.
+    #[serde(default)]
+    pub branch: String,
 }
 
 impl Version {
+    /// Creates a new version with the region and branch filled in.
+    pub fn new(version: String, path: String) -> Self {
+        let (region, branch) = match VERSION_STRING_REGEX.captures(&version) {
+            Some(captures) => (captures[1].to_string(), captures[2].to_string()),
+            None => (String::new(), String::new()),
+        };
+
+        Version {
+            version,
+            path,
+            region,
+            branch,
+        }
+    }
+
     /// Saves the profile to the database.
     ///
     /// If it already exists, it updates the values.
@@ -138,6 +298,11 @@ pub struct GameManager {
     pub versions: Vec<Version>,
     pub tools: Vec<Tool>,
     pub mods: Vec<Mod>,
+
+    /// An index of profile IDs to their position in `profiles`.
+    ///
+    /// This is rebuilt whenever profiles are loaded or saved.
+    profile_index: HashMap<String, usize>,
 }
 
 impl GameManager {
@@ -150,18 +315,46 @@ impl GameManager {
     ///
     /// This returns a clone of the profile data.
     pub fn get_profile<S: AsRef<str>>(&self, profile_id: S) -> Option<Profile> {
-        let profile_id = profile_id.as_ref();
-
-        self.profiles.iter().find(|p| p.id.eq(profile_id)).cloned()
+        self.profile_index
+            .get(profile_id.as_ref())
+            .and_then(|index| self.profiles.get(*index))
+            .cloned()
     }
 
     /// Saves the given profile to the database.
-    pub async fn save_profile(&mut self, mut profile: Profile) -> Result<()> {
-        // Set the profile ID.
-        profile.id = utils::random_id();
+    ///
+    /// If the profile doesn't have an ID, a new one is generated.\
+    /// This returns the saved profile.
+    pub async fn save_profile(&mut self, mut profile: Profile) -> Result<Profile> {
+        // Set the profile ID if it is a new profile.
+        if profile.id.is_empty() {
+            profile.id = utils::random_id();
+        }
 
         // Write the profile to the database.
-        profile.save().await
+        profile.save().await?;
+
+        // Update the loaded profiles.
+        match self.profile_index.get(&profile.id) {
+            Some(index) => self.profiles[*index] = profile.clone(),
+            None => {
+                self.profile_index
+                    .insert(profile.id.clone(), self.profiles.len());
+                self.profiles.push(profile.clone());
+            }
+        }
+
+        Ok(profile)
+    }
+
+    /// Rebuilds the profile index from the loaded profiles.
+    fn index_profiles(&mut self) {
+        self.profile_index = self
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(index, profile)| (profile.id.clone(), index))
+            .collect();
     }
 
     /// Loads all attributes from the database.
@@ -192,6 +385,7 @@ impl GameManager {
                 name: result.name,
                 icon: result.icon,
                 path: result.path,
+                sha256: result.sha256,
             });
         }
 
@@ -224,6 +418,7 @@ impl GameManager {
                 path: result.path,
                 version: result.version,
                 tool: tool.clone(),
+                sha256: result.sha256,
             });
         }
 
@@ -245,10 +440,8 @@ impl GameManager {
         // Parse versions.
         self.versions.clear();
         for result in results {
-            self.versions.push(Version {
-                version: result.version,
-                path: result.path,
-            });
+            self.versions
+                .push(Version::new(result.version, result.path));
         }
 
         Ok(())
@@ -317,12 +510,18 @@ impl GameManager {
                     }
                     None => Vec::new(),
                 },
-                launch_args: result.launch_args,
+                launch_args: parse_launch_args(&result.launch_args),
+                imported: result.imported,
+                wine_prefix: result.wine_prefix,
+                wine_dll_overrides: runners::parse_dll_overrides(&result.wine_dll_overrides),
             };
 
             self.profiles.push(profile);
         }
 
+        // Index the loaded profiles.
+        self.index_profiles();
+
         Ok(())
     }
 }
@@ -332,13 +531,18 @@ pub fn new_status_listener() -> WatchReceiver<bool> {
     GAME_STATUS.1.clone()
 }
 
+/// Checks if the game is currently being launched.
+pub fn is_launching() -> bool {
+    GAME_LAUNCHING.load(Ordering::Relaxed)
+}
+
 /// Utility method to check if the game is currently running.
 ///
 /// In the event of any errors, this will return `false`.
 #[tauri::command]
 pub fn game__is_open(profile: State<SelectedProfile>) -> bool {
     // Lock the selected profile.
-    let Some(ref profile) = *profile.0.lock().unwrap() else {
+    let Some(ref profile) = *profile.0.lock().recover() else {
         return false;
     };
 
@@ -346,6 +550,50 @@ pub fn game__is_open(profile: State<SelectedProfile>) -> bool {
     system::find_process(utils::get_executable_name(path))
 }
 
+/// The scheduling priority of the game process.
+///
+/// On Windows, this is the priority class of the game.\
+/// On Linux/macOS, this is the niceness of the runner, which the game runs in.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum ProcessPriority {
+    #[default]
+    Normal,
+    Above,
+    High,
+    Realtime,
+}
+
+impl ProcessPriority {
+    /// Parses the process priority from the game configuration.
+    ///
+    /// Unknown values fall back to `Normal`.
+    pub fn from_config(config: &Game) -> Self {
+        match config.process_priority.to_lowercase().as_str() {
+            "normal" => ProcessPriority::Normal,
+            "above" => ProcessPriority::Above,
+            "high" => ProcessPriority::High,
+            "realtime" => ProcessPriority::Realtime,
+            other => {
+                warn!("Unknown process priority '{}', using normal.", other);
+                ProcessPriority::Normal
+            }
+        }
+    }
+
+    /// Returns the niceness of the priority.
+    ///
+    /// Lower values are scheduled first.
+    #[cfg(unix)]
+    fn niceness(self) -> i32 {
+        match self {
+            ProcessPriority::Normal => 0,
+            ProcessPriority::Above => -5,
+            ProcessPriority::High => -10,
+            ProcessPriority::Realtime => -20,
+        }
+    }
+}
+
 /// Enables the 'process watcher'.
 ///
 /// This will look for the game process.
@@ -367,6 +615,13 @@ pub fn watch_game(profile: Profile) {
 
         // Once the game is open, notify listeners.
         sender.send(true).unwrap();
+        events::emit_global(Event::GameStatusChanged(true));
+        let opened = Instant::now();
+
+        // Start sniffing, if configured.
+        if Config::sniffer().auto_start_with_game {
+            sniffer::start_with_game();
+        }
 
         // Wait for the game to close.
         while system::find_process(utils::get_executable_name(&path)) {
@@ -375,9 +630,49 @@ pub fn watch_game(profile: Profile) {
 
         // Once the game is closed, notify listeners.
         sender.send(false).unwrap();
+        events::emit_global(Event::GameStatusChanged(false));
+
+        // Check if the game closed cleanly.
+        let code = take_exit_code();
+        let crashed = code.is_some_and(|code| code != 0);
+        if crashed {
+            warn!("The game exited with code {:#x}.", code.unwrap_or_default());
+        }
+
+        record_exit(crashed && opened.elapsed() < LAUNCH_CRASH_WINDOW);
+        events::emit_global(Event::GameExited { code, crashed });
     });
 }
 
+/// Records whether the game crashed shortly after launching.
+///
+/// Consecutive launch crashes are counted, and the count is reset on any other exit.
+fn record_exit(launch_crash: bool) {
+    let mut state = GLOBAL_STATE.write().recover();
+    state.crash_count = if launch_crash {
+        state.crash_count + 1
+    } else {
+        0
+    };
+
+    if let Err(error) = state.save() {
+        warn!("Failed to save the persistent state: {}", error);
+    }
+}
+
+/// Suggests launching the game without mods, if it crashed on the last launch.
+///
+/// This returns a localized suggestion, or `None` if the game didn't crash.
+#[tauri::command]
+pub fn game__safe_mode_suggestion() -> Option<String> {
+    let count = GLOBAL_STATE.read().recover().crash_count;
+    if count == 0 {
+        return None;
+    }
+
+    Some(t!("game.suggestion.safe-mode", count = count).to_string())
+}
+
 /// Launches the game.
 ///
 /// If the game is already open, this fails with a helpful error message.
@@ -393,198 +688,1258 @@ pub fn game__launch(profile: State<SelectedProfile>) -> MaybeError<()> {
         return Err("game.error.already-open");
     }
 
-    // Get the configuration.
-    let config = Config::get();
+    // Get the game configuration.
+    let config = Config::game();
 
     // Lock the selected profile.
-    let Some(ref profile) = *profile.0.lock().unwrap() else {
+    let Some(ref profile) = *profile.0.lock().recover() else {
         return Err("game.error.launch.no-profile");
     };
 
+    // Check that the game executable exists.
+    check_executable(profile)?;
+
+    // Check that the tools and mods haven't been modified.
+    verify_checksums(profile)?;
+
     // Run the game watcher.
     watch_game(profile.clone());
 
     // Launch the game.
-    launch_game(profile, config)
+    GAME_LAUNCHING.store(true, Ordering::Relaxed);
+    let result = launch_game(profile, &config);
+    GAME_LAUNCHING.store(false, Ordering::Relaxed);
+
+    result
 }
 
-/// Launches the game.
+/// Checks that the game executable of the profile exists.
 ///
-/// This is invoked from the CLI.
-pub async fn cli_game__launch(matches: &ArgMatches) {
-    let game_manager = GameManager::get().read().await;
-
-    // Get the default/selected profile, or the one specified.
-    let Some(profile) = (match matches.get_one::<String>("profile") {
-        Some(profile) => game_manager.get_profile(profile),
-        None => {
-            let state = GLOBAL_STATE.read().unwrap();
-            let Some(profile) = &state.selected_profile else {
-                warn!("{}", t!("game.error.launch.no-profile"));
-                return;
-            };
+/// This catches games that were moved or uninstalled before any launch work is done.
+fn check_executable(profile: &Profile) -> MaybeError<()> {
+    let path = &profile.version.path;
+    if system::resolve_path(path).is_ok_and(|path| path.is_file()) {
+        return Ok(());
+    }
 
-            game_manager.get_profile(profile)
-        }
-    }) else {
-        warn!("{}", t!("game.error.launch.invalid-profile"));
-        return;
-    };
+    warn!("The game executable was not found at '{}'.", path);
+    Err("game.error.launch.missing-executable")
+}
 
-    // Lock the configuration.
-    let config = Config::get();
+/// Returns the directories tools of the profile can be loaded from.
+///
+/// This is `None` if the profile is trusted, and tools can be loaded from anywhere.\
+/// Otherwise, this includes the game directory, the mods directory, and the app data directory.
+fn allowed_roots(profile: &Profile) -> Option<Vec<PathBuf>> {
+    if !profile.imported {
+        return None;
+    }
 
-    // Launch the game.
-    if let Err(error) = launch_game(&profile, config) {
-        warn!("{} {}", t!("launcher.error.profile.unknown"), error);
+    let mut roots = Vec::new();
+    if let Some(game_dir) = PathBuf::from(&profile.version.path).parent() {
+        roots.push(game_dir.to_path_buf());
+    }
+    if let Ok(app_data_dir) = utils::app_data_dir() {
+        roots.push(app_data_dir.join("mods"));
+        roots.push(app_data_dir);
     }
+
+    Some(roots)
 }
 
-/// Locates a game installation, then adds it to the version database.
-///
-/// # Errors
+/// Verifies the checksums of the profile's tools and mods.
 ///
-/// Errors are not localized and need to be looked up by the
-/// caller before displaying to the user.
-#[tauri::command]
-pub async fn game__locate(path: String) -> MaybeError<()> {
-    locate_game(path).await
-}
+/// Tools and mods without a checksum are not verified.
+fn verify_checksums(profile: &Profile) -> MaybeError<()> {
+    let files = profile
+        .tools
+        .iter()
+        .map(|tool| (&tool.name, &tool.path, &tool.sha256))
+        .chain(
+            profile
+                .mods
+                .iter()
+                .map(|r#mod| (&r#mod.name, &r#mod.path, &r#mod.sha256)),
+        );
 
-/// Locates an existing game installation.
-pub async fn locate_game(path: String) -> MaybeError<()> {
-    // Load the executable data into memory.
-    // "is there a better way to do this? probably not."
-    let executable_path = PathBuf::from(&path);
-    let Some(parent) = executable_path.parent() else {
-        return Err("backend.version.resolve.error");
-    };
+    for (name, path, expected) in files {
+        let Some(expected) = expected else {
+            continue;
+        };
 
-    // If a `UnityPlayer.dll` is found, use it for the version string lookup.
-    let unity_player = parent.join("UnityPlayer.dll");
-    let game_data = match std::fs::read(if unity_player.exists() {
-        unity_player
-    } else {
-        executable_path
-    }) {
-        Ok(data) => data,
-        Err(_) => return Err("backend.version.resolve.error"),
-    };
-    let game_data = String::from_utf8_lossy(&game_data);
+        // Hash the file and compare it to the stored checksum.
+        let actual = system::resolve_path(path)
+            .and_then(|path| utils::hash_file(&path, HashAlgorithm::Sha256));
+        match actual {
+            Ok(actual) if actual.eq_ignore_ascii_case(expected) => {}
+            Ok(_) => {
+                warn!("The checksum of '{}' does not match.", name);
+                return Err("game.error.launch.checksum-mismatch");
+            }
+            Err(error) => {
+                warn!("Failed to hash '{}': {}", name, error);
+                return Err("game.error.launch.checksum-mismatch");
+            }
+        }
+    }
 
-    // Match the version string.
-    let Some(captures) = VERSION_STRING_REGEX.captures(&game_data) else {
-        return Err("backend.version.resolve.error");
-    };
-    let Some(version_string) = captures.get(0) else {
-        return Err("backend.version.resolve.error");
-    };
-    let version_string = version_string.as_str();
+    Ok(())
+}
 
-    // Insert the game into the database.
-    let pool = database::get_pool();
+/// Hashes the file at the given path, for storing as a checksum.
+fn hash_file(path: &str) -> MaybeError<String> {
+    let hash =
+        system::resolve_path(path).and_then(|path| utils::hash_file(&path, HashAlgorithm::Sha256));
 
-    // Check if the version already exists.
-    match sqlx::query!(
-        "SELECT * FROM `versions` WHERE `version` = $1",
-        version_string
-    )
-    .fetch_one(&pool)
-    .await
-    {
-        Err(Error::RowNotFound) => (),
-        Ok(_) => return Err("backend.version.resolve.exists"),
-        _ => return Err("database.query-failed"),
+    match hash {
+        Ok(hash) => Ok(hash),
+        Err(error) => {
+            warn!("Failed to hash '{}': {}", path, error);
+            Err("game.error.checksum.hash-failed")
+        }
     }
+}
 
-    // Otherwise, insert the version.
-    let version = Version {
-        version: version_string.to_string(),
-        path,
-    };
+/// Reloads all game data, after a tool or mod was changed.
+///
+/// This also refreshes the selected profile, so it includes the changes.
+async fn reload_game_data(game_manager: &mut GameManager) -> MaybeError<()> {
+    if let Err(error) = game_manager.load_all().await {
+        warn!("Failed to reload game data: {}", error);
+        return Err("launcher.error.profile.unknown");
+    }
 
-    if let Err(error) = version.save().await {
-        warn!("Failed to insert version: {}", error);
-        return Err("database.query-failed");
-    };
+    let selected = GLOBAL_STATE.read().recover().selected_profile.clone();
+    if let Some(profile) = selected.and_then(|id| game_manager.get_profile(id)) {
+        state::select_profile(profile);
+    }
 
     Ok(())
 }
 
-// ------------------------------ BEWARE: Below is all platform-dependent code! ------------------------------ \\
-
-/// Internal method used to launch the game.
-///
-/// # On Linux/macOS
+/// Recomputes and stores the checksum of a tool.
 ///
-/// This uses a combination (or user preference) of Wine and Proton to run the game.
-///
-/// The game executable is run without privilege, then the
-/// game modifications specified in the configuration are loaded afterward.
-#[cfg(unix)]
-fn launch_game(_: MutexGuard<'_, Config>) -> MaybeError<()> {
-    Err("game.error.launch.unsupported")
-}
+/// This should be used after the tool's file was intentionally updated.\
+/// This returns the new checksum.
+#[tauri::command]
+pub async fn game__update_tool_hash(tool_id: String) -> MaybeError<String> {
+    let mut game_manager = GameManager::get().write().await;
+
+    // Find the tool by its ID.
+    let Some(mut tool) = game_manager
+        .tools
+        .iter()
+        .find(|tool| tool.id == tool_id)
+        .cloned()
+    else {
+        return Err("game.error.checksum.bad-id");
+    };
 
-// ------------------------------ BEWARE: Below is all Windows API code! ------------------------------ \\
+    // Hash the tool and save it.
+    let hash = hash_file(&tool.path)?;
+    tool.sha256 = Some(hash.clone());
 
-/// Internal method used to launch the game.
-///
-/// # On Windows
-///
-/// This uses the Windows API to launch the game in various steps:
-/// 1. Opening the game and obtaining a handle.
-/// 2. Disabling the anti-cheat if specified.
-/// 3. Injecting any DLLs specified by the user.
-#[cfg(windows)]
-fn launch_game(profile: &Profile, config: MutexGuard<'_, Config>) -> MaybeError<()> {
-    use log::warn;
-    use windows::Win32::Foundation::CloseHandle;
-    use windows::Win32::System::Threading::ResumeThread;
+    if let Err(error) = tool.save().await {
+        warn!("Failed to save tool: {}", error);
+        return Err("launcher.error.profile.unknown");
+    }
 
-    let game_config = &config.game;
-    let version = &profile.version;
+    reload_game_data(&mut game_manager).await?;
 
-    // 1. Launch the game and obtain handles.
-    let (thread, process) = open_game(&version.path, &profile.launch_args)?;
+    Ok(hash)
+}
 
-    // 2. Disable the anti-cheat if specified.
-    let disable_ac = game_config.disable_anti_cheat;
-    if disable_ac {
-        unsafe {
-            wait_for_driver(&process)?;
-        }
+/// Recomputes and stores the checksum of a mod.
+///
+/// This should be used after the mod's file was intentionally updated.\
+/// This returns the new checksum.
+#[tauri::command]
+pub async fn game__update_mod_hash(mod_id: String) -> MaybeError<String> {
+    let mut game_manager = GameManager::get().write().await;
+
+    // Find the mod by its ID.
+    let Some(mut r#mod) = game_manager
+        .mods
+        .iter()
+        .find(|r#mod| r#mod.id == mod_id)
+        .cloned()
+    else {
+        return Err("game.error.checksum.bad-id");
+    };
+
+    // Hash the mod and save it.
+    let hash = hash_file(&r#mod.path)?;
+    r#mod.sha256 = Some(hash.clone());
+
+    if let Err(error) = r#mod.save().await {
+        warn!("Failed to save mod: {}", error);
+        return Err("launcher.error.profile.unknown");
     }
 
-    // 3. Inject any DLLs specified by the user.
-    let load_library = unsafe {
-        use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+    reload_game_data(&mut game_manager).await?;
 
-        let kernel = "kernel32.dll".as_cstring();
-        let kernel = match GetModuleHandleA(sys_str!(kernel)) {
-            Ok(handle) => handle,
-            Err(_) => return Err("game.error.launch.unknown"),
-        };
+    Ok(hash)
+}
 
-        let load_library = "LoadLibraryA".as_cstring();
-        match GetProcAddress(kernel, sys_str!(load_library)) {
-            Some(ptr) => std::mem::transmute::<_, LPTHREAD_START_ROUTINE>(ptr),
-            None => return Err("game.error.launch.dll-fail"),
+/// Launches the game without any modifications.
+///
+/// This skips injecting the profile's tools and disabling the anti-cheat.\
+/// It is useful for checking whether a modification is causing issues.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__launch_vanilla(profile: State<SelectedProfile>) -> MaybeError<()> {
+    // Check if the game process is already running.
+    if game__is_open(profile.clone()) {
+        return Err("game.error.already-open");
+    }
+
+    // Lock the selected profile.
+    let Some(ref profile) = *profile.0.lock().recover() else {
+        return Err("game.error.launch.no-profile");
+    };
+
+    // Check that the game executable exists.
+    check_executable(profile)?;
+
+    // Run the game watcher.
+    watch_game(profile.clone());
+
+    // Launch the game.
+    GAME_LAUNCHING.store(true, Ordering::Relaxed);
+    let result = launch_vanilla(profile);
+    GAME_LAUNCHING.store(false, Ordering::Relaxed);
+
+    result
+}
+
+/// Injects a DLL into the running game.
+///
+/// This is useful for testing a modification without relaunching the game.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__inject(profile: State<SelectedProfile>, path: String) -> MaybeError<()> {
+    // Find the game process.
+    let Some(ref profile) = *profile.0.lock().recover() else {
+        return Err("game.error.launch.no-profile");
+    };
+    let executable = utils::get_executable_name(&profile.version.path);
+    let Some(process_id) = system::find_process_id(executable) else {
+        return Err("game.error.inject.not-running");
+    };
+
+    // Check that the DLL exists and can be loaded by the game.
+    let Ok(dll_path) = system::resolve_path(&path) else {
+        return Err("game.error.inject.bad-path");
+    };
+    if !dll_path.is_file() || dll_path.extension().is_none_or(|ext| ext != "dll") {
+        return Err("game.error.inject.bad-path");
+    }
+
+    match system::executable_machine(&dll_path) {
+        Ok(system::MACHINE_AMD64) => (),
+        Ok(_) => return Err("game.error.inject.bad-architecture"),
+        Err(_) => return Err("game.error.inject.bad-path"),
+    }
+
+    inject_live(process_id, dll_path.to_string_lossy().to_string())
+}
+
+/// Unloads a DLL from the running game.
+///
+/// The module is found by its file name, such as `example.dll`.\
+/// Together with `game__inject`, this allows reloading a DLL without relaunching the game.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__eject(profile: State<SelectedProfile>, module_name: String) -> MaybeError<()> {
+    // Find the game process.
+    let Some(ref profile) = *profile.0.lock().recover() else {
+        return Err("game.error.launch.no-profile");
+    };
+    let executable = utils::get_executable_name(&profile.version.path);
+    let Some(process_id) = system::find_process_id(executable) else {
+        return Err("game.error.inject.not-running");
+    };
+
+    eject_live(process_id, &module_name)
+}
+
+/// A module loaded by the running game.
+#[derive(Serialize, Debug, Clone)]
+pub struct GameModule {
+    /// The file name of the module. (e.g. `example.dll`)
+    pub name: String,
+
+    /// The base address the module is loaded at.
+    pub base: u64,
+
+    /// The size of the module in memory, in bytes.
+    pub size: u32,
+}
+
+/// Lists the modules loaded by the running game.
+///
+/// This shows which tools and mods were actually loaded.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__modules(profile: State<SelectedProfile>) -> MaybeError<Vec<GameModule>> {
+    list_modules(running_game_id(&profile)?)
+}
+
+/// Pauses the running game.
+///
+/// Every thread of the game is suspended until `game__resume` is called.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__suspend(profile: State<SelectedProfile>) -> MaybeError<()> {
+    set_suspended(running_game_id(&profile)?, true)
+}
+
+/// Resumes the running game after it was paused with `game__suspend`.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__resume(profile: State<SelectedProfile>) -> MaybeError<()> {
+    set_suspended(running_game_id(&profile)?, false)
+}
+
+/// Finds the process ID of the selected profile's running game.
+fn running_game_id(profile: &State<SelectedProfile>) -> MaybeError<u32> {
+    let Some(ref profile) = *profile.0.lock().recover() else {
+        return Err("game.error.launch.no-profile");
+    };
+
+    let executable = utils::get_executable_name(&profile.version.path);
+    system::find_process_id(executable).ok_or("game.error.process.not-running")
+}
+
+/// Waits for the game's main window to open.
+///
+/// This returns the window handle, or fails once the timeout (in seconds) has passed.\
+/// By default, this waits for up to 60 seconds.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn game__wait_for_window(
+    profile: State<'_, SelectedProfile>,
+    timeout: Option<u64>,
+) -> MaybeError<u64> {
+    if cfg!(unix) {
+        return Err("game.error.launch.unsupported");
+    }
+
+    // Get the game executable.
+    let executable = match *profile.0.lock().recover() {
+        Some(ref profile) => utils::get_executable_name(&profile.version.path),
+        None => return Err("game.error.launch.no-profile"),
+    };
+
+    let deadline = Instant::now() + Duration::from_secs(timeout.unwrap_or(60));
+    loop {
+        // Look for the window of the game process.
+        let window = system::find_process_id(&executable).and_then(find_game_window);
+        if let Some(window) = window {
+            events::emit_global(Event::GameWindowFound(window));
+            return Ok(window);
+        }
+
+        if Instant::now() >= deadline {
+            return Err("game.error.window.timeout");
+        }
+
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// A step of the launch plan, for a single tool or mod.
+#[derive(Serialize, Debug)]
+pub struct LaunchStep {
+    /// The name of the tool or mod.
+    pub name: String,
+
+    /// The resolved path of the tool or mod.
+    pub path: String,
+
+    /// What the launch would do with the file.
+    ///
+    /// This is `inject` for DLLs, `open` for executables, `mod` for mods, or `skip`.
+    pub action: &'static str,
+
+    /// Why the file would be skipped, if it would be.
+    pub error: Option<&'static str>,
+}
+
+/// A preview of what launching a profile would do.
+#[derive(Serialize, Debug)]
+pub struct LaunchReport {
+    /// The command line the game would be opened with, if it can be built.
+    pub command_line: Option<String>,
+
+    /// Why the launch would fail before opening the game, if it would.
+    pub error: Option<&'static str>,
+
+    /// Whether the anti-cheat would be disabled.
+    pub disable_anti_cheat: bool,
+
+    /// The steps for each tool and mod of the profile.
+    pub steps: Vec<LaunchStep>,
+}
+
+/// The command the game is opened with.
+#[derive(Serialize, Debug, Clone)]
+pub struct LaunchCommand {
+    /// The program which is run.
+    ///
+    /// This is the game executable on Windows, and the runner on Linux/macOS.
+    pub executable: String,
+
+    /// The arguments passed to the program.
+    pub arguments: String,
+
+    /// The full command line, with the executable quoted.
+    pub command_line: String,
+
+    /// The environment variables set for the game.
+    ///
+    /// The game otherwise inherits the environment of the launcher.
+    pub environment: BTreeMap<String, String>,
+}
+
+/// Builds the command the game of the profile is opened with.
+///
+/// This describes the same command the game is launched with,
+/// including the runner and its environment on Linux/macOS.
+pub fn launch_command(profile: &Profile) -> MaybeError<LaunchCommand> {
+    let command = runners::game_command(profile)?;
+
+    let executable = command.get_program().to_string_lossy().to_string();
+    let arguments = command
+        .get_args()
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect::<Vec<String>>();
+
+    let arguments = utils::join_args(&arguments);
+    let mut command_line = utils::quote_arg(&executable);
+    if !arguments.is_empty() {
+        command_line = format!("{} {}", command_line, arguments);
+    }
+
+    // Only variables set for the game are included.
+    let environment = command
+        .get_envs()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .map(|(key, value)| {
+            (
+                key.to_string_lossy().to_string(),
+                value.to_string_lossy().to_string(),
+            )
+        })
+        .collect();
+
+    Ok(LaunchCommand {
+        executable,
+        arguments,
+        command_line,
+        environment,
+    })
+}
+
+/// Returns the command the selected profile's game is opened with.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__command_line(profile: State<SelectedProfile>) -> MaybeError<LaunchCommand> {
+    match *profile.0.lock().recover() {
+        Some(ref profile) => launch_command(profile),
+        None => Err("game.error.launch.no-profile"),
+    }
+}
+
+/// Previews launching the selected profile, without opening the game.
+///
+/// Each tool and mod is resolved and checked the same way a real launch would.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn game__simulate_launch(profile: State<SelectedProfile>) -> MaybeError<LaunchReport> {
+    let Some(ref profile) = *profile.0.lock().recover() else {
+        return Err("game.error.launch.no-profile");
+    };
+
+    // Run the checks done before the game is opened.
+    let command = launch_command(profile);
+    let error = check_executable(profile)
+        .and_then(|_| verify_checksums(profile))
+        .and_then(|_| command.as_ref().map(|_| ()).map_err(|error| *error))
+        .err();
+
+    // Resolve each tool the same way the launch does.
+    let roots = allowed_roots(profile);
+    let mut steps = Vec::new();
+    for tool in &profile.tools {
+        let path = match &roots {
+            Some(roots) => system::resolve_path_within(&tool.path, roots),
+            None => system::resolve_path(&tool.path),
+        };
+
+        let (path, action, error) = match path {
+            Ok(path) if path.is_file() => {
+                let extension = path
+                    .extension()
+                    .map(|ext| ext.to_string_lossy().to_string());
+                let (action, error) = match extension.as_deref() {
+                    Some("dll") => match system::executable_machine(&path) {
+                        Ok(system::MACHINE_AMD64) => ("inject", None),
+                        Ok(_) => ("skip", Some("game.error.inject.bad-architecture")),
+                        Err(_) => ("skip", Some("game.error.inject.bad-path")),
+                    },
+                    Some("exe") => ("open", None),
+                    _ => ("skip", Some("game.error.launch.unknown-tool")),
+                };
+
+                (path.to_string_lossy().to_string(), action, error)
+            }
+            _ => (
+                tool.path.clone(),
+                "skip",
+                Some("backend.path.error.modification"),
+            ),
+        };
+
+        steps.push(LaunchStep {
+            name: tool.name.clone(),
+            path,
+            action,
+            error,
+        });
+    }
+
+    // Mods are loaded by their tools, so only their paths are checked.
+    for r#mod in &profile.mods {
+        let exists = system::resolve_path(&r#mod.path).is_ok_and(|path| path.exists());
+        steps.push(LaunchStep {
+            name: r#mod.name.clone(),
+            path: r#mod.path.clone(),
+            action: if exists { "mod" } else { "skip" },
+            error: (!exists).then_some("backend.path.error.modification"),
+        });
+    }
+
+    Ok(LaunchReport {
+        command_line: command.ok().map(|command| command.command_line),
+        error,
+        disable_anti_cheat: Config::game().disable_anti_cheat,
+        steps,
+    })
+}
+
+/// Launches the game.
+///
+/// This is invoked from the CLI.
+pub async fn cli_game__launch(matches: &ArgMatches) {
+    let game_manager = GameManager::get().read().await;
+
+    // Get the default/selected profile, or the one specified.
+    let Some(profile) = (match matches.get_one::<String>("profile") {
+        Some(profile) => game_manager.get_profile(profile),
+        None => {
+            let state = GLOBAL_STATE.read().recover();
+            let Some(profile) = &state.selected_profile else {
+                warn!("{}", t!("game.error.launch.no-profile"));
+                return;
+            };
+
+            game_manager.get_profile(profile)
+        }
+    }) else {
+        warn!("{}", t!("game.error.launch.invalid-profile"));
+        return;
+    };
+
+    // Check that the game executable exists.
+    if check_executable(&profile).is_err() {
+        let path = &profile.version.path;
+        warn!(
+            "{}",
+            t!("game.error.launch.missing-executable", path = path)
+        );
+        return;
+    }
+
+    // Suggest launching without mods if the game keeps crashing.
+    if let Some(suggestion) = game__safe_mode_suggestion() {
+        warn!("{}", suggestion);
+    }
+
+    // Get the game configuration.
+    let config = Config::game();
+
+    // Launch the game.
+    if let Err(error) = launch_game(&profile, &config).translated() {
+        warn!("{}", error);
+    }
+}
+
+/// Locates a game installation, then adds it to the version database.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn game__locate(path: String) -> MaybeError<()> {
+    locate_game(path).await.map(|_| ())
+}
+
+/// Prompts the user to select the game executable, then adds it to the version database.
+///
+/// This returns the detected version, or `None` if the user cancelled.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn game__locate_interactive(app_handle: AppHandle) -> MaybeError<Option<String>> {
+    // Open a file picker for the game executable.
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app_handle
+        .dialog()
+        .file()
+        .set_title(t!("launcher.dialog.locate-game"))
+        .add_filter(t!("launcher.dialog.game-executable"), &["exe"])
+        .pick_file(move |file| {
+            let _ = tx.send(file);
+        });
+
+    // If the user cancelled, do nothing.
+    let Ok(Some(file)) = rx.await else {
+        return Ok(None);
+    };
+
+    let Ok(path) = file.into_path() else {
+        return Err("game.error.launch.bad-path");
+    };
+
+    locate_game(path.to_string_lossy().to_string())
+        .await
+        .map(Some)
+}
+
+/// The names of the game executable.
+const GAME_EXECUTABLES: [&str; 2] = ["GenshinImpact.exe", "YuanShen.exe"];
+
+/// Directories the game is commonly installed to, relative to a drive root.
+const GAME_INSTALL_DIRS: [&str; 4] = [
+    "Program Files/HoYoPlay/games/Genshin Impact game",
+    "Program Files/Genshin Impact/Genshin Impact game",
+    "Program Files/miHoYo Launcher/games/Genshin Impact Game",
+    "Program Files/Genshin Impact Game",
+];
+
+/// A game installation found by `game__autodetect`.
+#[derive(Serialize, Debug)]
+pub struct DetectedGame {
+    /// The path to the game executable.
+    pub path: String,
+
+    /// The detected version of the game.
+    pub version: String,
+}
+
+/// Searches common install locations for the game.
+///
+/// This checks the official launcher's registry entries and the default install directories.\
+/// Installations which are already added are skipped.\
+/// The user should confirm each candidate, which is then added with `game__locate`.
+#[tauri::command]
+pub async fn game__autodetect() -> Vec<DetectedGame> {
+    // Collect the directories the game may be installed in.
+    let mut install_dirs = registry_install_dirs();
+    for drive in ['C', 'D', 'E', 'F'] {
+        for dir in GAME_INSTALL_DIRS {
+            install_dirs.push(PathBuf::from(format!("{}:/", drive)).join(dir));
+        }
+    }
+
+    let game_manager = GameManager::get().read().await;
+    let mut detected: Vec<DetectedGame> = Vec::new();
+
+    for dir in install_dirs {
+        for executable in GAME_EXECUTABLES {
+            let path = dir.join(executable);
+            if !path.is_file() {
+                continue;
+            }
+
+            // Skip installations which were already found or added.
+            let path = path.to_string_lossy().to_string();
+            let known = game_manager
+                .versions
+                .iter()
+                .any(|version| PathBuf::from(&version.path) == PathBuf::from(&path));
+            if known || detected.iter().any(|game| game.path == path) {
+                continue;
+            }
+
+            match detect_version(&path) {
+                Ok(version) => detected.push(DetectedGame { path, version }),
+                Err(error) => warn!("Failed to detect the version of '{}': {}", path, error),
+            }
+        }
+    }
+
+    detected
+}
+
+/// Locates an existing game installation.
+///
+/// This returns the detected version.
+pub async fn locate_game(path: String) -> MaybeError<String> {
+    let version_string = detect_version(&path)?;
+    let version_string = version_string.as_str();
+
+    // Insert the game into the database.
+    let pool = database::get_pool();
+
+    // Check if the version already exists.
+    match sqlx::query!(
+        "SELECT * FROM `versions` WHERE `version` = $1",
+        version_string
+    )
+    .fetch_one(&pool)
+    .await
+    {
+        Err(Error::RowNotFound) => (),
+        Ok(_) => return Err("backend.version.resolve.exists"),
+        _ => return Err("database.query-failed"),
+    }
+
+    // Otherwise, insert the version.
+    let version = Version::new(version_string.to_string(), path);
+
+    if let Err(error) = version.save().await {
+        warn!("Failed to insert version: {}", error);
+        return Err("database.query-failed");
+    };
+
+    Ok(version.version)
+}
+
+/// Detects the version string of the game at the given path.
+fn detect_version(path: &str) -> MaybeError<String> {
+    // Load the executable data into memory.
+    // "is there a better way to do this? probably not."
+    let executable_path = PathBuf::from(path);
+    let Some(parent) = executable_path.parent() else {
+        return Err("backend.version.resolve.error");
+    };
+
+    // If a `UnityPlayer.dll` is found, use it for the version string lookup.
+    let unity_player = parent.join("UnityPlayer.dll");
+    let game_data = match std::fs::read(if unity_player.exists() {
+        unity_player
+    } else {
+        executable_path
+    }) {
+        Ok(data) => data,
+        Err(_) => return Err("backend.version.resolve.error"),
+    };
+    let game_data = String::from_utf8_lossy(&game_data);
+
+    // Match the version string.
+    let Some(captures) = VERSION_STRING_REGEX.captures(&game_data) else {
+        return Err("backend.version.resolve.error");
+    };
+    let Some(version_string) = captures.get(0) else {
+        return Err("backend.version.resolve.error");
+    };
+
+    // Cross-check the version with the game's configuration file.
+    if let Some(config) = read_game_config(parent) {
+        let number = format!("{}.{}.{}", &captures[3], &captures[4], &captures[5]);
+        if !config.game_version.is_empty() && config.game_version != number {
+            warn!(
+                "The game's config.ini reports version {}, but the game is version {}.",
+                config.game_version, number
+            );
+        }
+
+        if config.region().is_some_and(|region| region != &captures[1]) {
+            warn!(
+                "The game's config.ini reports a different region than {}.",
+                &captures[1]
+            );
+        }
+    }
+
+    Ok(version_string.as_str().to_string())
+}
+
+/// The contents of a game's `config.ini` file.
+///
+/// This is written by the official launcher next to the game executable.
+#[derive(Default, Debug)]
+pub struct GameConfig {
+    /// The installed game version. (e.g. `4.5.0`)
+    pub game_version: String,
+
+    /// The distribution channel of the game.
+    pub channel: String,
+
+    /// The publisher of the game. (e.g. `mihoyo`, `bilibili`)
+    pub cps: String,
+}
+
+impl GameConfig {
+    /// Returns the region the game was published for.
+    ///
+    /// This is `None` if the publisher doesn't identify a region.
+    pub fn region(&self) -> Option<&'static str> {
+        match self.cps.as_str() {
+            "hoyoverse" => Some("OS"),
+            "bilibili" => Some("CN"),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the `config.ini` file in the given game directory.
+///
+/// This returns `None` if the file doesn't exist or can't be read.
+pub fn read_game_config(game_dir: &Path) -> Option<GameConfig> {
+    let contents = std::fs::read_to_string(game_dir.join("config.ini")).ok()?;
+
+    let mut config = GameConfig::default();
+    let mut in_general = false;
+    for line in contents.lines() {
+        let line = line.trim();
+
+        // Only the `[General]` section contains version information.
+        if line.starts_with('[') {
+            in_general = line.eq_ignore_ascii_case("[General]");
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if !in_general {
+            continue;
+        }
+
+        let value = value.trim().to_string();
+        match key.trim() {
+            "game_version" => config.game_version = value,
+            "channel" => config.channel = value,
+            "cps" => config.cps = value,
+            _ => (),
+        }
+    }
+
+    Some(config)
+}
+
+/// The result of relocating a game version.
+#[derive(Serialize, Debug)]
+#[serde(tag = "result", content = "version", rename_all = "kebab-case")]
+pub enum RelocateResult {
+    /// The version's path was updated.
+    Relocated,
+
+    /// The game at the new path is a different version.
+    ///
+    /// It can be added with `game__locate`.
+    DifferentVersion(String),
+}
+
+/// Updates the path of a game version, after the game was moved.
+///
+/// If the game at the new path is a different version, nothing is changed.\
+/// The different version is returned instead, so the user can choose to add it.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn version__relocate(
+    old_version: String,
+    new_path: String,
+) -> MaybeError<RelocateResult> {
+    // Check that the new path is a game executable.
+    let is_executable = system::resolve_path(&new_path).is_ok_and(|path| path.is_file());
+    if !is_executable || !new_path.ends_with(".exe") {
+        return Err("game.error.launch.bad-path");
+    }
+
+    let mut game_manager = GameManager::get().write().await;
+
+    // Find the version being relocated.
+    let Some(mut version) = game_manager
+        .versions
+        .iter()
+        .find(|version| version.version == old_version)
+        .cloned()
+    else {
+        return Err("backend.version.relocate.unknown");
+    };
+
+    // Check that the game at the new path is the same version.
+    let version_string = detect_version(&new_path)?;
+    if version_string != version.version {
+        return Ok(RelocateResult::DifferentVersion(version_string));
+    }
+
+    // Update the version's path.
+    version.path = new_path;
+    if let Err(error) = version.save().await {
+        warn!("Failed to update version: {}", error);
+        return Err("database.query-failed");
+    }
+
+    reload_game_data(&mut game_manager).await?;
+
+    Ok(RelocateResult::Relocated)
+}
+
+// ------------------------------ BEWARE: Below is all platform-dependent code! ------------------------------ \\
+
+/// Internal method used to launch the game.
+///
+/// # On Linux/macOS
+///
+/// This uses a combination (or user preference) of Wine and Proton to run the game.
+///
+/// The game executable is run without privilege, in the profile's Wine prefix.\
+/// Tools are injected with the Windows API, so they can't be loaded yet.
+#[cfg(unix)]
+fn launch_game(profile: &Profile, _: &Game) -> MaybeError<()> {
+    if !profile.tools.is_empty() {
+        warn!("Tools can't be injected when running with Wine, so they are skipped.");
+    }
+
+    launch_vanilla(profile)
+}
+
+/// Reads the game install directories from the official launcher's registry entries.
+///
+/// # On Linux/macOS
+///
+/// There is no registry, so this returns nothing.
+#[cfg(unix)]
+fn registry_install_dirs() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+/// Internal method used to read the exit code of the launched game.
+///
+/// # On Linux/macOS
+///
+/// The game process isn't tracked, so this returns nothing.
+#[cfg(unix)]
+fn take_exit_code() -> Option<i32> {
+    None
+}
+
+/// Internal method used to launch the game without modifications.
+///
+/// # On Linux/macOS
+///
+/// This runs the game with the configured or detected Wine runner.
+#[cfg(unix)]
+fn launch_vanilla(profile: &Profile) -> MaybeError<()> {
+    events::emit_global(Event::GameLaunchProgress("open".to_string()));
+
+    let mut child = match runners::game_command(profile)?.spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            warn!("Failed to launch the game with Wine: {}", error);
+            return Err("launcher.error.wine.launch-failed");
+        }
+    };
+    set_priority(child.id(), ProcessPriority::from_config(&Config::game()));
+
+    // Reap the runner once it exits.
+    std::thread::spawn(move || child.wait());
+
+    events::emit_global(Event::GameLaunchProgress("done".to_string()));
+
+    Ok(())
+}
+
+/// Internal method used on Linux/macOS to set the priority of the game process.
+///
+/// Raising the priority usually requires root, so failing to set it only logs a warning.
+#[cfg(unix)]
+fn set_priority(pid: u32, priority: ProcessPriority) {
+    // New processes already have the normal priority.
+    if priority == ProcessPriority::Normal {
+        return;
+    }
+
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid, priority.niceness()) };
+    if result != 0 {
+        let error = std::io::Error::last_os_error();
+        warn!("Failed to set the game's priority: {}", error);
+    }
+}
+
+/// Internal method used to inject a DLL into the running game.
+#[cfg(unix)]
+fn inject_live(_: u32, _: String) -> MaybeError<()> {
+    Err("game.error.launch.unsupported")
+}
+
+/// Internal method used to unload a DLL from the running game.
+#[cfg(unix)]
+fn eject_live(_: u32, _: &str) -> MaybeError<()> {
+    Err("game.error.launch.unsupported")
+}
+
+/// Internal method used to list the modules loaded by the running game.
+#[cfg(unix)]
+fn list_modules(_: u32) -> MaybeError<Vec<GameModule>> {
+    Err("game.error.launch.unsupported")
+}
+
+/// Internal method used to suspend or resume the running game.
+#[cfg(unix)]
+fn set_suspended(_: u32, _: bool) -> MaybeError<()> {
+    Err("game.error.launch.unsupported")
+}
+
+/// Internal method used to find the main window of the game process.
+///
+/// # On Linux/macOS
+///
+/// The game can't be launched, so there is no window to find.
+#[cfg(unix)]
+fn find_game_window(_: u32) -> Option<u64> {
+    None
+}
+
+// ------------------------------ BEWARE: Below is all Windows API code! ------------------------------ \\
+
+/// Reads the game install directories from the official launcher's registry entries.
+///
+/// # On Windows
+///
+/// The launcher stores the install path under the current user's registry hive.
+#[cfg(windows)]
+fn registry_install_dirs() -> Vec<PathBuf> {
+    use windows::core::w;
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+    let keys = [
+        w!("Software\\Cognosphere\\HYP\\1_0\\hk4e_global"),
+        w!("Software\\miHoYo\\HYP\\1_1\\hk4e_cn"),
+    ];
+
+    let mut dirs = Vec::new();
+    for key in keys {
+        let mut buffer = [0u16; 1024];
+        let mut size = (buffer.len() * 2) as u32;
+
+        let result = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key,
+                w!("GameInstallPath"),
+                RRF_RT_REG_SZ,
+                None,
+                Some(buffer.as_mut_ptr() as *mut _),
+                Some(&mut size),
+            )
+        };
+        if result.is_err() {
+            continue;
+        }
+
+        // The size includes the null terminator, in bytes.
+        let length = (size as usize / 2).saturating_sub(1);
+        dirs.push(PathBuf::from(String::from_utf16_lossy(&buffer[..length])));
+    }
+
+    dirs
+}
+
+/// Stores the handle of the launched game process.
+///
+/// The handle is closed once the exit code is read.
+#[cfg(windows)]
+fn track_process(process: HANDLE) {
+    use windows::Win32::Foundation::CloseHandle;
+
+    let previous = GAME_PROCESS.lock().recover().replace(process.0 as usize);
+    if let Some(previous) = previous {
+        unsafe {
+            _ = CloseHandle(HANDLE(previous as *mut _));
+        }
+    }
+}
+
+/// Internal method used to read the exit code of the launched game.
+///
+/// # On Windows
+///
+/// This reads the exit code from the process handle kept by `track_process`.\
+/// Returns `None` if the game wasn't launched by the launcher.
+#[cfg(windows)]
+fn take_exit_code() -> Option<i32> {
+    use windows::Win32::Foundation::{CloseHandle, STILL_ACTIVE};
+    use windows::Win32::System::Threading::{GetExitCodeProcess, WaitForSingleObject};
+
+    let process = HANDLE(GAME_PROCESS.lock().recover().take()? as *mut _);
+
+    let code = unsafe {
+        // Give the process a moment to finish exiting.
+        _ = WaitForSingleObject(process, 5000);
+
+        let mut code = 0u32;
+        let result = GetExitCodeProcess(process, &mut code);
+        _ = CloseHandle(process);
+
+        result.ok()?;
+        code
+    };
+
+    if code == STILL_ACTIVE.0 as u32 {
+        return None;
+    }
+
+    Some(code as i32)
+}
+
+/// The handles of a game opened with `open_game`.
+///
+/// When dropped, the game is resumed and the thread handle is closed.\
+/// This also happens when a launch step fails, so the handles are never leaked.
+#[cfg(windows)]
+struct GameHandles {
+    thread: HANDLE,
+    process: HANDLE,
+
+    /// Whether the whole process was suspended with `suspend`.
+    suspended: bool,
+}
+
+#[cfg(windows)]
+impl Drop for GameHandles {
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Threading::ResumeThread;
+
+        unsafe {
+            if self.suspended {
+                _ = resume(&self.process);
+            }
+            _ = ResumeThread(self.thread);
+            _ = CloseHandle(self.thread);
+        }
+
+        // The process handle is kept for reading the exit code.
+        track_process(self.process);
+    }
+}
+
+/// Internal method used to launch the game.
+///
+/// # On Windows
+///
+/// This uses the Windows API to launch the game in various steps:
+/// 1. Opening the game and obtaining a handle.
+/// 2. Disabling the anti-cheat if specified.
+/// 3. Injecting any DLLs specified by the user.
+#[cfg(windows)]
+fn launch_game(profile: &Profile, game_config: &Game) -> MaybeError<()> {
+    use log::warn;
+
+    let version = &profile.version;
+
+    // 1. Launch the game and obtain handles.
+    events::emit_global(Event::GameLaunchProgress("open".to_string()));
+    let (thread, process) = open_game(&version.path, &profile.launch_args)?;
+    let mut handles = GameHandles {
+        thread,
+        process,
+        suspended: false,
+    };
+    set_priority(&process, ProcessPriority::from_config(game_config));
+    if let Some(cores) = &game_config.cpu_affinity {
+        set_affinity(&process, cores);
+    }
+
+    // 2. Disable the anti-cheat if specified.
+    let disable_ac = game_config.disable_anti_cheat;
+    if disable_ac {
+        events::emit_global(Event::GameLaunchProgress("anti-cheat".to_string()));
+        unsafe {
+            wait_for_driver(&process, game_config)?;
         }
+    }
+
+    // 3. Inject any DLLs specified by the user.
+    let Some(load_library) =
+        (unsafe { resolve_proc::<LPTHREAD_START_ROUTINE>("kernel32.dll", "LoadLibraryA") })
+    else {
+        return Err("game.error.launch.dll-fail");
     };
 
     if !disable_ac {
         unsafe {
             suspend(&process)?;
         }
+        handles.suspended = true;
     }
 
     // Inject all DLLs in the configuration.
+    events::emit_global(Event::GameLaunchProgress("inject".to_string()));
+    let roots = allowed_roots(profile);
     for tool in &profile.tools {
         // Resolve the tool's path.
-        let Ok(path) = system::resolve_path(&tool.path) else {
-            warn!("{}", t!("backend.path.error.modification"));
-            continue;
+        // Imported profiles can only load tools from the allowed roots.
+        let path = match &roots {
+            Some(roots) => system::resolve_path_within(&tool.path, roots),
+            None => system::resolve_path(&tool.path),
+        };
+        let path = match path {
+            Ok(path) => path,
+            Err(error) => {
+                warn!("{} {}", t!("backend.path.error.modification"), error);
+                continue;
+            }
         };
 
         if !path.exists() {
@@ -616,15 +1971,332 @@ fn launch_game(profile: &Profile, config: MutexGuard<'_, Config>) -> MaybeError<
         unsafe {
             resume(&process)?;
         }
+        handles.suspended = false;
     }
 
-    // Finally, clean up any left-over handles.
+    // Finally, let the game run and clean up the handles.
+    drop(handles);
+
+    events::emit_global(Event::GameLaunchProgress("done".to_string()));
+
+    Ok(())
+}
+
+/// Internal method used to launch the game without modifications.
+///
+/// # On Windows
+///
+/// This only opens the game; the anti-cheat and injection steps are skipped.
+#[cfg(windows)]
+fn launch_vanilla(profile: &Profile) -> MaybeError<()> {
+    // Launch the game and obtain handles.
+    events::emit_global(Event::GameLaunchProgress("open".to_string()));
+    let (thread, process) = open_game(&profile.version.path, &profile.launch_args)?;
+    let handles = GameHandles {
+        thread,
+        process,
+        suspended: false,
+    };
+    let game_config = Config::game();
+    set_priority(&process, ProcessPriority::from_config(&game_config));
+    if let Some(cores) = &game_config.cpu_affinity {
+        set_affinity(&process, cores);
+    }
+
+    // Let the game run and clean up the handles.
+    drop(handles);
+
+    events::emit_global(Event::GameLaunchProgress("done".to_string()));
+
+    Ok(())
+}
+
+/// Internal method used to inject a DLL into the running game.
+///
+/// # On Windows
+///
+/// This opens the game process by its ID, then injects the DLL with `LoadLibrary`.
+#[cfg(windows)]
+fn inject_live(process_id: u32, dll_path: String) -> MaybeError<()> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{OpenProcess, PROCESS_ALL_ACCESS};
+
+    let Some(load_library) =
+        (unsafe { resolve_proc::<LPTHREAD_START_ROUTINE>("kernel32.dll", "LoadLibraryA") })
+    else {
+        return Err("game.error.launch.dll-fail");
+    };
+
+    // Open the game process.
+    let process = match unsafe { OpenProcess(PROCESS_ALL_ACCESS, false, process_id) } {
+        Ok(handle) => handle,
+        Err(_) => return Err("game.error.launch.not-elevated"),
+    };
+
+    let result = unsafe { inject_dll(&process, load_library, dll_path) };
+
     unsafe {
-        _ = ResumeThread(thread);
         _ = CloseHandle(process);
     }
 
-    Ok(())
+    result
+}
+
+/// Internal method used to unload a DLL from the running game.
+///
+/// # On Windows
+///
+/// This finds the module's base address with `list_modules`,
+/// then calls `FreeLibrary` in the game through a remote thread.
+#[cfg(windows)]
+fn eject_live(process_id: u32, module_name: &str) -> MaybeError<()> {
+    use windows::Win32::Foundation::{CloseHandle, WAIT_OBJECT_0};
+    use windows::Win32::System::Threading::{
+        CreateRemoteThread, OpenProcess, WaitForSingleObject, PROCESS_ALL_ACCESS,
+    };
+
+    let Some(free_library) =
+        (unsafe { resolve_proc::<LPTHREAD_START_ROUTINE>("kernel32.dll", "FreeLibrary") })
+    else {
+        return Err("game.error.inject.eject-failed");
+    };
+
+    // Find the module by its name.
+    let Some(module) = list_modules(process_id)?
+        .into_iter()
+        .find(|module| module.name.eq_ignore_ascii_case(module_name))
+    else {
+        return Err("game.error.inject.not-loaded");
+    };
+
+    // Open the game process.
+    let process = match unsafe { OpenProcess(PROCESS_ALL_ACCESS, false, process_id) } {
+        Ok(handle) => handle,
+        Err(_) => return Err("game.error.launch.not-elevated"),
+    };
+
+    let result = unsafe {
+        // Invoke the FreeLibrary function with the module's base address.
+        match CreateRemoteThread(
+            process,
+            None,
+            0,
+            free_library,
+            Some(module.base as *const _),
+            0,
+            None,
+        ) {
+            Ok(thread) => {
+                let finished = WaitForSingleObject(thread, 2000) == WAIT_OBJECT_0;
+                _ = CloseHandle(thread);
+
+                if finished {
+                    Ok(())
+                } else {
+                    Err("game.error.inject.eject-failed")
+                }
+            }
+            Err(_) => Err("game.error.inject.eject-failed"),
+        }
+    };
+
+    unsafe {
+        _ = CloseHandle(process);
+    }
+
+    result
+}
+
+/// The window class of the game's main window.
+#[cfg(windows)]
+const GAME_WINDOW_CLASS: &str = "UnityWndClass";
+
+/// Internal method used to list the modules loaded by the running game.
+///
+/// # On Windows
+///
+/// This enumerates the modules with `EnumProcessModules`,
+/// then reads their names and sizes with `GetModuleBaseNameW` and `GetModuleInformation`.
+#[cfg(windows)]
+fn list_modules(process_id: u32) -> MaybeError<Vec<GameModule>> {
+    use std::mem::size_of;
+    use windows::Win32::Foundation::{CloseHandle, ERROR_ACCESS_DENIED, HMODULE};
+    use windows::Win32::System::ProcessStatus::{
+        EnumProcessModules, GetModuleBaseNameW, GetModuleInformation, MODULEINFO,
+    };
+    use windows::Win32::System::Threading::{
+        OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+    };
+
+    /// Reads the modules of the opened process.
+    unsafe fn modules(process: HANDLE) -> windows::core::Result<Vec<GameModule>> {
+        // Grow the buffer until every module fits.
+        let mut handles = vec![HMODULE::default(); 256];
+        loop {
+            let size = (handles.len() * size_of::<HMODULE>()) as u32;
+            let mut needed = 0;
+            EnumProcessModules(process, handles.as_mut_ptr(), size, &mut needed)?;
+
+            let count = needed as usize / size_of::<HMODULE>();
+            if count <= handles.len() {
+                handles.truncate(count);
+                break;
+            }
+
+            handles.resize(count, HMODULE::default());
+        }
+
+        let mut modules = Vec::with_capacity(handles.len());
+        for handle in handles {
+            let mut name = [0u16; 260];
+            let length = GetModuleBaseNameW(process, Some(handle), &mut name);
+
+            let mut info = MODULEINFO::default();
+            GetModuleInformation(process, handle, &mut info, size_of::<MODULEINFO>() as u32)?;
+
+            modules.push(GameModule {
+                name: String::from_utf16_lossy(&name[..length as usize]),
+                base: info.lpBaseOfDll as u64,
+                size: info.SizeOfImage,
+            });
+        }
+
+        Ok(modules)
+    }
+
+    // Protected processes deny access to their modules.
+    let to_error = |error: windows::core::Error| {
+        if error.code() == ERROR_ACCESS_DENIED.to_hresult() {
+            return "game.error.process.access-denied";
+        }
+
+        warn!("Failed to list the game's modules: {}", error);
+        "game.error.process.open-failed"
+    };
+
+    // Open the game process.
+    let access = PROCESS_QUERY_INFORMATION | PROCESS_VM_READ;
+    let process = unsafe { OpenProcess(access, false, process_id) }.map_err(to_error)?;
+
+    let result = unsafe { modules(process) }.map_err(to_error);
+
+    unsafe {
+        _ = CloseHandle(process);
+    }
+
+    result
+}
+
+/// Internal method used to suspend or resume the running game.
+///
+/// # On Windows
+///
+/// This opens the game process by its ID, then uses the NT API to suspend or resume it.
+#[cfg(windows)]
+fn set_suspended(process_id: u32, suspended: bool) -> MaybeError<()> {
+    use windows::Win32::Foundation::CloseHandle;
+    use windows::Win32::System::Threading::{OpenProcess, PROCESS_SUSPEND_RESUME};
+
+    // Open the game process.
+    let process = match unsafe { OpenProcess(PROCESS_SUSPEND_RESUME, false, process_id) } {
+        Ok(handle) => handle,
+        Err(error) => {
+            warn!("Failed to open the game process: {}", error);
+            return Err("game.error.process.open-failed");
+        }
+    };
+
+    let result = unsafe {
+        if suspended {
+            suspend(&process)
+        } else {
+            resume(&process)
+        }
+    };
+
+    unsafe {
+        _ = CloseHandle(process);
+    }
+
+    result
+}
+
+/// Internal method used to find the main window of the game process.
+///
+/// # On Windows
+///
+/// This enumerates all top-level windows with `EnumWindows`,
+/// looking for a visible window of the game's class owned by the process.
+#[cfg(windows)]
+fn find_game_window(process_id: u32) -> Option<u64> {
+    use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+    use windows::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GetClassNameW, GetWindowThreadProcessId, IsWindowVisible,
+    };
+
+    /// The state passed to the enumeration callback.
+    struct Search {
+        process_id: u32,
+        window: Option<u64>,
+    }
+
+    unsafe extern "system" fn callback(window: HWND, lparam: LPARAM) -> BOOL {
+        let search = &mut *(lparam.0 as *mut Search);
+
+        // Skip windows of other processes.
+        let mut owner = 0u32;
+        GetWindowThreadProcessId(window, Some(&mut owner));
+        if owner != search.process_id || !IsWindowVisible(window).as_bool() {
+            return true.into();
+        }
+
+        // Skip windows which aren't the game's main window, such as the splash screen.
+        let mut class = [0u16; 256];
+        let length = GetClassNameW(window, &mut class);
+        if String::from_utf16_lossy(&class[..length as usize]) != GAME_WINDOW_CLASS {
+            return true.into();
+        }
+
+        // Returning `false` stops the enumeration.
+        search.window = Some(window.0 as u64);
+        false.into()
+    }
+
+    let mut search = Search {
+        process_id,
+        window: None,
+    };
+
+    // This errors when the enumeration is stopped early, so the result is ignored.
+    unsafe {
+        _ = EnumWindows(Some(callback), LPARAM(&mut search as *mut Search as isize));
+    }
+
+    search.window
+}
+
+/// Internal method used on Windows systems to resolve a function exported by a module.
+///
+/// The module must already be loaded by the launcher, such as `kernel32.dll` or `ntdll`.\
+/// Returns `None` if the module isn't loaded, or doesn't export the function.
+///
+/// # Safety
+///
+/// `T` must be a function pointer type which matches the signature of the export.
+#[cfg(windows)]
+unsafe fn resolve_proc<T: Copy>(module: &str, name: &str) -> Option<T> {
+    use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
+
+    // Function pointers are the size of an address.
+    const { assert!(std::mem::size_of::<T>() == std::mem::size_of::<usize>()) };
+
+    let module = module.as_cstring();
+    let module = GetModuleHandleA(sys_str!(module)).ok()?;
+
+    let name = name.as_cstring();
+    let ptr = GetProcAddress(module, sys_str!(name))?;
+
+    Some(std::mem::transmute_copy::<_, T>(&ptr))
 }
 
 /// This type is used by both 'suspend' and 'resume' methods.
@@ -636,22 +2308,11 @@ type NtSuspendProcess = unsafe extern "system" fn(HANDLE) -> i32;
 /// This uses the NT API to suspend the process.
 #[cfg(windows)]
 unsafe fn suspend(process: &HANDLE) -> MaybeError<()> {
-    use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
-
-    let nt = "ntdll".as_cstring();
-    let nt_module = match GetModuleHandleA(sys_str!(nt)) {
-        Ok(handle) => handle,
-        Err(_) => return Err("game.error.launch.unknown"),
-    };
-
-    let suspend = "NtSuspendProcess".as_cstring();
-    let ptr = match GetProcAddress(nt_module, sys_str!(suspend)) {
-        Some(ptr) => ptr,
-        None => return Err("game.error.launch.unknown"),
+    let Some(func) = resolve_proc::<NtSuspendProcess>("ntdll", "NtSuspendProcess") else {
+        return Err("game.error.launch.unknown");
     };
 
     // Call the function.
-    let func = std::mem::transmute::<_, NtSuspendProcess>(ptr);
     func(*process);
 
     Ok(())
@@ -662,22 +2323,11 @@ unsafe fn suspend(process: &HANDLE) -> MaybeError<()> {
 /// This uses the NT API to resume the process.
 #[cfg(windows)]
 unsafe fn resume(process: &HANDLE) -> MaybeError<()> {
-    use windows::Win32::System::LibraryLoader::{GetModuleHandleA, GetProcAddress};
-
-    let nt = "ntdll".as_cstring();
-    let nt_module = match GetModuleHandleA(sys_str!(nt)) {
-        Ok(handle) => handle,
-        Err(_) => return Err("game.error.launch.unknown"),
-    };
-
-    let resume = "NtResumeProcess".as_cstring();
-    let ptr = match GetProcAddress(nt_module, sys_str!(resume)) {
-        Some(ptr) => ptr,
-        None => return Err("game.error.launch.unknown"),
+    let Some(func) = resolve_proc::<NtSuspendProcess>("ntdll", "NtResumeProcess") else {
+        return Err("game.error.launch.unknown");
     };
 
     // Call the function.
-    let func = std::mem::transmute::<_, NtSuspendProcess>(ptr);
     func(*process);
 
     Ok(())
@@ -687,7 +2337,7 @@ unsafe fn resume(process: &HANDLE) -> MaybeError<()> {
 ///
 /// This returns the handles of the thread and process.
 #[cfg(windows)]
-fn open_game(path: &String, launch_args: &str) -> Result<(HANDLE, HANDLE), &'static str> {
+fn open_game(path: &String, launch_args: &[String]) -> Result<(HANDLE, HANDLE), &'static str> {
     use std::mem::size_of;
     use sysinfo::System;
     use windows::Win32::Foundation::HANDLE;
@@ -754,7 +2404,7 @@ fn open_game(path: &String, launch_args: &str) -> Result<(HANDLE, HANDLE), &'sta
         };
 
         let path = path.as_cstring();
-        let arguments = launch_args.as_cstring();
+        let arguments = utils::join_args(launch_args).as_cstring();
 
         let result = CreateProcessAsUserA(
             Some(token),
@@ -780,11 +2430,71 @@ fn open_game(path: &String, launch_args: &str) -> Result<(HANDLE, HANDLE), &'sta
     }
 }
 
+/// Internal method used on Windows systems to set the priority of the game process.
+///
+/// Failing to set the priority isn't fatal, so this only logs a warning.
+#[cfg(windows)]
+fn set_priority(process: &HANDLE, priority: ProcessPriority) {
+    use windows::Win32::System::Threading::{
+        SetPriorityClass, ABOVE_NORMAL_PRIORITY_CLASS, HIGH_PRIORITY_CLASS, REALTIME_PRIORITY_CLASS,
+    };
+
+    // New processes already have the normal priority.
+    let class = match priority {
+        ProcessPriority::Normal => return,
+        ProcessPriority::Above => ABOVE_NORMAL_PRIORITY_CLASS,
+        ProcessPriority::High => HIGH_PRIORITY_CLASS,
+        ProcessPriority::Realtime => REALTIME_PRIORITY_CLASS,
+    };
+
+    if let Err(error) = unsafe { SetPriorityClass(*process, class) } {
+        warn!("Failed to set the game's priority: {}", error);
+    }
+}
+
+/// Internal method used on Windows systems to set the CPU cores the game can run on.
+///
+/// Cores which don't exist are skipped with a warning.\
+/// If no valid cores are given, the affinity is left unchanged.
+#[cfg(windows)]
+fn set_affinity(process: &HANDLE, cores: &[usize]) {
+    use windows::Win32::System::Threading::SetProcessAffinityMask;
+
+    let core_count = std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+        .min(usize::BITS as usize);
+
+    // Build the affinity mask from the core indices.
+    let mut mask = 0usize;
+    for &core in cores {
+        if core >= core_count {
+            warn!(
+                "CPU core {} does not exist, there are only {} cores.",
+                core, core_count
+            );
+            continue;
+        }
+
+        mask |= 1 << core;
+    }
+
+    if mask == 0 {
+        warn!("No valid CPU cores were given, the game can run on any core.");
+        return;
+    }
+
+    if let Err(error) = unsafe { SetProcessAffinityMask(*process, mask) } {
+        warn!("Failed to set the game's CPU affinity: {}", error);
+    }
+}
+
 /// Internal method used on Windows systems to disable the anti-cheat.
 ///
-/// This works by suspending the process until the anti-cheat driver is unloaded.
+/// This works by suspending the process until the anti-cheat driver is unloaded.\
+/// If the driver doesn't load and unload within the configured timeout, the process is resumed.
 #[cfg(windows)]
-unsafe fn wait_for_driver(process: &HANDLE) -> MaybeError<()> {
+unsafe fn wait_for_driver(process: &HANDLE, game_config: &Game) -> MaybeError<()> {
     use std::{
         ffi::c_void,
         mem::{size_of, size_of_val},
@@ -792,22 +2502,30 @@ unsafe fn wait_for_driver(process: &HANDLE) -> MaybeError<()> {
     };
     use windows::Win32::System::ProcessStatus::{EnumDeviceDrivers, GetDeviceDriverBaseNameA};
 
-    unsafe fn driver_loaded() -> MaybeError<bool> {
+    unsafe fn driver_loaded(driver_names: &[&str]) -> MaybeError<bool> {
         let mut needed = 0;
-        let mut drivers: [*mut c_void; 1024] = [ptr::null_mut(); 1024];
+        let mut drivers: Vec<*mut c_void> = vec![ptr::null_mut(); 1024];
 
         // Get the list of drivers.
-        if EnumDeviceDrivers(drivers.as_mut_ptr(), size_of::<u32>() as u32, &mut needed).is_err() {
-            return Err("game.error.launch.unknown");
-        }
+        // `EnumDeviceDrivers` takes the size of the buffer in bytes, and writes
+        // the number of bytes needed to hold every driver's address to `needed`.
+        loop {
+            let size = size_of_val(drivers.as_slice()) as u32;
+            if EnumDeviceDrivers(drivers.as_mut_ptr(), size, &mut needed).is_err() {
+                return Err("game.error.launch.unknown");
+            }
 
-        // Check if there is an under-allocation of the list.
-        if needed > size_of_val(&drivers) as u32 {
-            return Err("game.error.launch.unknown");
+            // If more bytes are needed than the buffer holds, the list was truncated.
+            // Grow the buffer to fit every driver, then try again.
+            if needed <= size {
+                break;
+            }
+
+            drivers.resize(needed as usize / size_of::<*mut c_void>(), ptr::null_mut());
         }
 
         // Enumerate over all drivers.
-        let driver_count = needed as usize / size_of_val(&drivers[0]);
+        let driver_count = needed as usize / size_of::<*mut c_void>();
         for driver in drivers.iter().take(driver_count) {
             let mut name = [0u8; 256];
             let size = GetDeviceDriverBaseNameA(*driver, &mut name);
@@ -818,7 +2536,7 @@ unsafe fn wait_for_driver(process: &HANDLE) -> MaybeError<()> {
 
             // Convert the name into a string.
             if let Ok(name) = String::from_utf8(name[..size as usize].to_vec()) {
-                if name.starts_with(dotenv!("GAME_DRIVER_NAME")) {
+                if driver_names.iter().any(|prefix| name.starts_with(prefix)) {
                     return Ok(true);
                 }
             }
@@ -827,19 +2545,46 @@ unsafe fn wait_for_driver(process: &HANDLE) -> MaybeError<()> {
         Ok(false)
     }
 
-    let mut found = false;
+    let driver_names = game_config.driver_names();
+    let driver_name = driver_names.join(", ");
+    let deadline = Instant::now() + Duration::from_secs(game_config.anti_cheat_timeout);
 
     // Wait until the driver is first found.
-    while !found {
-        found = driver_loaded()?;
-        std::thread::sleep(std::time::Duration::from_millis(100));
+    while !driver_loaded(&driver_names)? {
+        if Instant::now() >= deadline {
+            warn!("The anti-cheat driver '{}' was never loaded.", driver_name);
+            return Err("game.error.launch.ac-timeout");
+        }
+
+        std::thread::sleep(Duration::from_millis(100));
     }
 
     // Wait for the driver to unload.
     suspend(process)?;
 
-    while driver_loaded()? {
-        std::thread::sleep(std::time::Duration::from_millis(100));
+    loop {
+        // Always resume the process, so the game isn't left frozen.
+        let loaded = match driver_loaded(&driver_names) {
+            Ok(loaded) => loaded,
+            Err(error) => {
+                resume(process)?;
+                return Err(error);
+            }
+        };
+        if !loaded {
+            break;
+        }
+
+        if Instant::now() >= deadline {
+            warn!(
+                "The anti-cheat driver '{}' was never unloaded.",
+                driver_name
+            );
+            resume(process)?;
+            return Err("game.error.launch.ac-timeout");
+        }
+
+        std::thread::sleep(Duration::from_millis(100));
     }
 
     resume(process)?;
@@ -876,6 +2621,9 @@ unsafe fn inject_dll(
         MEM_RESERVE | MEM_COMMIT,
         PAGE_READWRITE,
     );
+    if dll_path.is_null() {
+        return Err("game.error.launch.dll-fail");
+    }
 
     // Write the DLL path to the process.
     if WriteProcessMemory(
@@ -887,6 +2635,9 @@ unsafe fn inject_dll(
     )
     .is_err()
     {
+        // Free the memory.
+        _ = VirtualFreeEx(*process, dll_path, 0, MEM_RELEASE);
+
         return Err("game.error.launch.dll-fail");
     };
 
@@ -909,3 +2660,167 @@ unsafe fn inject_dll(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::{Mutex as AsyncMutex, MutexGuard as AsyncMutexGuard};
+
+    /// The tests share the global database pool, so only one can use it at a time.
+    static DATABASE: AsyncMutex<()> = AsyncMutex::const_new(());
+
+    /// Opens a new in-memory database with a version, two tools, and a mod.
+    ///
+    /// The database is used until the returned guard is dropped.
+    async fn open_database() -> AsyncMutexGuard<'static, ()> {
+        let guard = DATABASE.lock().await;
+        database::initialize_with("sqlite::memory:").await.unwrap();
+
+        version().save().await.unwrap();
+        tool("tool-a").save().await.unwrap();
+        tool("tool-b").save().await.unwrap();
+        mod_for("mod-a", tool("tool-a")).save().await.unwrap();
+
+        guard
+    }
+
+    /// Loads a new game manager from the database.
+    async fn load_manager() -> GameManager {
+        let mut game_manager = GameManager::default();
+        game_manager.load_all().await.unwrap();
+
+        game_manager
+    }
+
+    fn version() -> Version {
+        Version::new(
+            "OSRELWin5.0.0".to_string(),
+            "/games/GenshinImpact.exe".to_string(),
+        )
+    }
+
+    fn tool(id: &str) -> Tool {
+        Tool {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: format!("/tools/{id}.dll"),
+            ..Default::default()
+        }
+    }
+
+    fn mod_for(id: &str, tool: Tool) -> Mod {
+        Mod {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: format!("/mods/{id}"),
+            version: "1.0.0".to_string(),
+            tool,
+            ..Default::default()
+        }
+    }
+
+    fn profile(id: &str, name: &str) -> Profile {
+        Profile {
+            id: id.to_string(),
+            name: name.to_string(),
+            version: version(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn profile_round_trips_through_the_database() {
+        let _database = open_database().await;
+
+        let mut saved = profile("profile", "Modded");
+        saved.tools = vec![tool("tool-b"), tool("tool-a")];
+        saved.mods = vec![mod_for("mod-a", tool("tool-a"))];
+        saved.launch_args = vec!["-screen-width".to_string(), "1920".to_string()];
+        saved.wine_prefix = Some("/prefixes/game".to_string());
+        saved.wine_dll_overrides = BTreeMap::from([("dxgi".to_string(), "n,b".to_string())]);
+        saved.save().await.unwrap();
+
+        let game_manager = load_manager().await;
+        let loaded = game_manager.get_profile("profile").unwrap();
+
+        assert_eq!(loaded.name, "Modded");
+        assert_eq!(loaded.version.version, "OSRELWin5.0.0");
+        assert_eq!(loaded.version.region, "OS");
+        assert_eq!(loaded.launch_args, saved.launch_args);
+        assert_eq!(loaded.wine_prefix, saved.wine_prefix);
+        assert_eq!(loaded.wine_dll_overrides, saved.wine_dll_overrides);
+    }
+
+    #[tokio::test]
+    async fn profile_links_tools_and_mods_by_id() {
+        let _database = open_database().await;
+
+        let mut saved = profile("profile", "Linked");
+        saved.tools = vec![tool("tool-b"), tool("unknown"), tool("tool-a")];
+        saved.mods = vec![mod_for("mod-a", tool("tool-a"))];
+        saved.save().await.unwrap();
+
+        let game_manager = load_manager().await;
+        let loaded = game_manager.get_profile("profile").unwrap();
+
+        // Tools keep their order, and unknown IDs are skipped.
+        let tools: Vec<&str> = loaded.tools.iter().map(|tool| tool.id.as_str()).collect();
+        assert_eq!(tools, ["tool-b", "tool-a"]);
+        assert_eq!(loaded.tools[0].path, "/tools/tool-b.dll");
+
+        let mods: Vec<&str> = loaded.mods.iter().map(|r#mod| r#mod.id.as_str()).collect();
+        assert_eq!(mods, ["mod-a"]);
+        assert_eq!(loaded.mods[0].tool.id, "tool-a");
+    }
+
+    #[tokio::test]
+    async fn saving_a_profile_again_updates_it() {
+        let _database = open_database().await;
+
+        let mut saved = profile("profile", "Before");
+        saved.save().await.unwrap();
+        saved.name = "After".to_string();
+        saved.save().await.unwrap();
+
+        let rows: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM `profiles`")
+            .fetch_one(&database::get_pool())
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+
+        let game_manager = load_manager().await;
+        assert_eq!(game_manager.profiles.len(), 1);
+        assert_eq!(game_manager.profiles[0].name, "After");
+    }
+
+    #[tokio::test]
+    async fn profile_index_follows_loads_and_saves() {
+        let _database = open_database().await;
+        profile("first", "First").save().await.unwrap();
+        profile("second", "Second").save().await.unwrap();
+
+        // Profiles are found after loading.
+        let mut game_manager = load_manager().await;
+        assert_eq!(game_manager.get_profile("first").unwrap().name, "First");
+        assert_eq!(game_manager.get_profile("second").unwrap().name, "Second");
+
+        // New profiles are found after inserting them.
+        let inserted = game_manager
+            .save_profile(profile("", "Inserted"))
+            .await
+            .unwrap();
+        assert!(!inserted.id.is_empty());
+        let found = game_manager.get_profile(&inserted.id).unwrap();
+        assert_eq!(found.name, "Inserted");
+
+        // Updated profiles replace the old ones.
+        game_manager
+            .save_profile(profile("first", "Renamed"))
+            .await
+            .unwrap();
+        assert_eq!(game_manager.get_profile("first").unwrap().name, "Renamed");
+        assert_eq!(game_manager.get_profile("second").unwrap().name, "Second");
+        assert_eq!(game_manager.profiles.len(), 3);
+        assert!(game_manager.get_profile("unknown").is_none());
+    }
+}
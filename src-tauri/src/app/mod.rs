@@ -1,9 +1,18 @@
 use crate::events;
 use crate::events::Event;
+use crate::system;
+use crate::utils::{self, HashAlgorithm, MaybeError, Recover};
+use crate::GLOBAL_STATE;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use sysinfo::System;
 use tauri::AppHandle;
 
 pub mod appearance;
+pub mod diagnostics;
 pub mod game;
+pub mod mods;
+pub mod runners;
 pub mod sniffer;
 pub mod profile;
 
@@ -16,3 +25,234 @@ pub fn set_language(app_handle: AppHandle, language: String) {
     // Emit the language change event.
     events::emit_event(&app_handle, Event::LanguageChanged(language));
 }
+
+/// The locale which other locales fall back to.
+const FALLBACK_LOCALE: &str = "en-us";
+
+/// The fallback translations, which every locale should cover.
+const FALLBACK_TRANSLATIONS: &str = include_str!("../../../resources/lang/en-us.yaml");
+
+/// The translations of each locale, by the name of their file.
+///
+/// New locale files in `resources/lang` need to be added here to be audited.
+const TRANSLATIONS: &[(&str, &str)] = &[(FALLBACK_LOCALE, FALLBACK_TRANSLATIONS)];
+
+/// Why a translation key needs to be translated.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum TranslationIssue {
+    /// The locale doesn't have the key, so the fallback text is shown.
+    Missing,
+
+    /// The locale has the key, but it is the same as the fallback text.
+    Untranslated,
+}
+
+/// A translation key which a locale needs to translate.
+#[derive(Serialize, Debug)]
+pub struct MissingTranslation {
+    /// The translation key. (e.g. `cli.about`)
+    pub key: String,
+
+    /// Why the key needs to be translated.
+    pub issue: TranslationIssue,
+
+    /// The fallback text of the key.
+    pub fallback: String,
+}
+
+/// Lists the translation keys which a locale doesn't translate.
+///
+/// The keys are taken from the fallback `en-us` translations.\
+/// Each key is looked up in the locale's own file, without falling back to `en-us`.
+///
+/// The fallback locale translates every key, so auditing it returns nothing.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn i18n__audit(locale: String) -> MaybeError<Vec<MissingTranslation>> {
+    if locale.eq_ignore_ascii_case(FALLBACK_LOCALE) {
+        return Ok(Vec::new());
+    }
+
+    let fallback = parse_translations(FALLBACK_TRANSLATIONS)?;
+
+    // Locales without a file are missing every key.
+    let translations = match TRANSLATIONS
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(&locale))
+    {
+        Some((_, data)) => parse_translations(data)?,
+        None => BTreeMap::new(),
+    };
+
+    let issues = fallback
+        .into_iter()
+        .filter_map(|(key, fallback)| {
+            let issue = match translations.get(&key) {
+                None => TranslationIssue::Missing,
+                Some(text) if *text == fallback => TranslationIssue::Untranslated,
+                Some(_) => return None,
+            };
+
+            Some(MissingTranslation {
+                key,
+                issue,
+                fallback,
+            })
+        })
+        .collect();
+
+    Ok(issues)
+}
+
+/// Parses the translations of a locale file.
+///
+/// Keys starting with `_` hold metadata about the file, so they are skipped.
+fn parse_translations(data: &str) -> MaybeError<BTreeMap<String, String>> {
+    let translations: BTreeMap<String, serde_yml::Value> = match serde_yml::from_str(data) {
+        Ok(translations) => translations,
+        Err(error) => {
+            warn!("Failed to read the translations: {}", error);
+            return Err("backend.i18n.audit-failed");
+        }
+    };
+
+    Ok(translations
+        .into_iter()
+        .filter(|(key, _)| !key.starts_with('_'))
+        .filter_map(|(key, value)| Some((key, value.as_str()?.to_string())))
+        .collect())
+}
+
+/// Checks if the application can be closed without losing work.
+///
+/// This returns `false` if the game is launching or the sniffer is capturing.
+#[tauri::command]
+pub fn app__can_close() -> bool {
+    !game::is_launching() && !sniffer::is_running()
+}
+
+/// Checks if this is the first run of the application.
+///
+/// The frontend shows the onboarding while this is `true`.
+#[tauri::command]
+pub fn app__is_first_run() -> bool {
+    GLOBAL_STATE.read().recover().first_run
+}
+
+/// Marks the onboarding as completed.
+#[tauri::command]
+pub fn app__complete_onboarding() -> MaybeError<()> {
+    let mut state = GLOBAL_STATE.write().recover();
+    state.first_run = false;
+
+    if let Err(error) = state.save() {
+        warn!("Failed to save the persistent state: {}", error);
+        return Err("backend.state.save-failed");
+    }
+
+    Ok(())
+}
+
+/// The version and build metadata of the application.
+#[derive(Serialize, Debug)]
+pub struct AppInfo {
+    /// The version of the application.
+    pub version: String,
+
+    /// The target triple the application was built for.
+    pub target: String,
+
+    /// Whether this is a debug build.
+    pub debug: bool,
+
+    /// The name and version of the operating system.
+    pub os: String,
+
+    /// Whether the application is running elevated.
+    pub elevated: bool,
+}
+
+/// Collects the version and build metadata of the application.
+pub fn app_info() -> AppInfo {
+    AppInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        target: env!("TARGET_TRIPLE").to_string(),
+        debug: cfg!(debug_assertions),
+        os: System::long_os_version().unwrap_or_else(|| std::env::consts::OS.to_string()),
+        elevated: system::is_elevated(),
+    }
+}
+
+/// Returns the version and build metadata of the application.
+///
+/// This is useful for bug reports.
+#[tauri::command]
+pub fn app__info() -> AppInfo {
+    app_info()
+}
+
+/// Returns the data directory, if it was overridden for this run.
+///
+/// The webview keeps its stores here instead of in the platform's data directory.
+#[tauri::command]
+pub fn app__data_dir() -> Option<String> {
+    if !utils::has_custom_data_dir() {
+        return None;
+    }
+
+    utils::app_data_dir()
+        .ok()
+        .map(|path| path.to_string_lossy().to_string())
+}
+
+/// Computes the hash of a file, as a lowercase hex string.
+///
+/// The algorithm is either `sha256` or `md5`, defaulting to `sha256`.
+#[tauri::command]
+pub fn file__hash(path: String, algo: Option<HashAlgorithm>) -> MaybeError<String> {
+    let algorithm = algo.unwrap_or_default();
+
+    match system::resolve_path(&path).and_then(|file| utils::hash_file(&file, algorithm)) {
+        Ok(hash) => Ok(hash),
+        Err(error) => {
+            warn!("Failed to hash '{}': {}", path, error);
+            Err("backend.file.error.unreadable")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_locales_are_missing_every_key() {
+        let issues = i18n__audit("xx-xx".to_string()).unwrap();
+
+        assert!(issues.iter().any(|issue| issue.key == "cli.about"));
+        assert!(issues
+            .iter()
+            .all(|issue| matches!(issue.issue, TranslationIssue::Missing)));
+    }
+
+    #[test]
+    fn the_fallback_locale_has_nothing_to_translate() {
+        assert!(i18n__audit("en-us".to_string()).unwrap().is_empty());
+        assert!(i18n__audit("EN-US".to_string()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn metadata_keys_are_not_translations() {
+        let translations = parse_translations("_version: 1\ncli.about: \"About\"").unwrap();
+
+        assert_eq!(
+            translations,
+            BTreeMap::from([("cli.about".to_string(), "About".to_string())])
+        );
+    }
+}
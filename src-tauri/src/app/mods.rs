@@ -0,0 +1,116 @@
+use crate::events;
+use crate::events::Event;
+use crate::utils;
+use crate::utils::{MaybeError, Recover};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+/// The watcher over the mods directory, if it is running.
+static MODS_WATCHER: LazyLock<Mutex<Option<RecommendedWatcher>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// How long a file must go unchanged before it is reported.
+///
+/// Copying a file emits many events, so this waits for it to finish.
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// The file extensions which are reported as mods.
+const MOD_EXTENSIONS: [&str; 2] = ["dll", "zip"];
+
+/// Checks if the path looks like a mod file.
+fn is_mod_file(path: &Path) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_lowercase())
+        .is_some_and(|extension| MOD_EXTENSIONS.contains(&extension.as_str()))
+}
+
+/// Starts watching the mods directory for new mods.
+///
+/// New `.dll` and `.zip` files emit a `ysc://mods/discovered` event.\
+/// This does nothing if the watcher is already running.
+pub fn start_watcher() -> anyhow::Result<()> {
+    let mut watcher_lock = MODS_WATCHER.lock().recover();
+    if watcher_lock.is_some() {
+        return Ok(());
+    }
+
+    let mods_dir = utils::app_data_dir()?.join("mods");
+    let (tx, rx) = mpsc::channel::<PathBuf>();
+
+    // Forward new files to the debouncing thread.
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+
+        if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+            for path in event.paths.into_iter().filter(|path| is_mod_file(path)) {
+                let _ = tx.send(path);
+            }
+        }
+    })?;
+    watcher.watch(&mods_dir, RecursiveMode::NonRecursive)?;
+
+    // Report files once they stop changing.
+    // The thread stops when the watcher is dropped.
+    std::thread::spawn(move || {
+        let mut pending: HashMap<PathBuf, Instant> = HashMap::new();
+
+        loop {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(path) => {
+                    pending.insert(path, Instant::now());
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            pending.retain(|path, last_changed| {
+                if last_changed.elapsed() < DEBOUNCE {
+                    return true;
+                }
+
+                if path.exists() {
+                    events::emit_global(Event::ModDiscovered {
+                        path: path.to_string_lossy().to_string(),
+                    });
+                }
+
+                false
+            });
+        }
+    });
+
+    *watcher_lock = Some(watcher);
+
+    Ok(())
+}
+
+/// Stops watching the mods directory.
+///
+/// This is safe to call multiple times.
+pub fn stop_watcher() {
+    MODS_WATCHER.lock().recover().take();
+}
+
+/// Starts watching the mods directory for new mods.
+#[tauri::command]
+pub fn mods__watch_start() -> MaybeError<()> {
+    match start_watcher() {
+        Ok(()) => Ok(()),
+        Err(error) => {
+            warn!("Failed to watch the mods directory: {}", error);
+            Err("launcher.error.mods.watch-failed")
+        }
+    }
+}
+
+/// Stops watching the mods directory.
+#[tauri::command]
+pub fn mods__watch_stop() {
+    stop_watcher();
+}
@@ -1,8 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
 use tauri::State;
-use crate::app::game::{GameManager, Profile};
-use crate::GLOBAL_STATE;
+use crate::app::game::{GameManager, Mod, Profile, Tool};
+use crate::app::runners;
+use crate::{system, GLOBAL_STATE};
+use crate::state;
 use crate::state::SelectedProfile;
-use crate::utils::MaybeError;
+use crate::utils::{self, MaybeError, Recover};
+
+/// The current version of the profile descriptor format.
+///
+/// Format 2 stores the launch arguments as a list.
+const DESCRIPTOR_FORMAT: u32 = 2;
+
+/// A self-contained description of a profile, used for sharing it.
+///
+/// This includes the metadata of referenced tools and mods, but not their files.
+#[derive(Serialize, Deserialize)]
+struct ProfileDescriptor {
+    format: u32,
+    profile: Profile,
+}
+
+/// The result of importing a profile.
+#[derive(Serialize)]
+pub struct ImportedProfile {
+    /// The imported profile.
+    pub profile: Profile,
+
+    /// Tools referenced by the profile which aren't registered.
+    ///
+    /// These should be relinked by the user.
+    pub missing_tools: Vec<Tool>,
+
+    /// Mods referenced by the profile which aren't registered.
+    ///
+    /// These should be relinked by the user.
+    pub missing_mods: Vec<Mod>,
+}
 
 /// Fetches all available profiles.
 #[tauri::command]
@@ -11,6 +47,14 @@ pub async fn profile__get_all() -> Vec<Profile> {
     game_manager.profiles.clone()
 }
 
+/// Fetches the currently selected profile.
+///
+/// Returns `None` if no profile is selected.
+#[tauri::command]
+pub fn profile__get_selected(state: State<'_, SelectedProfile>) -> Option<Profile> {
+    state.0.lock().recover().clone()
+}
+
 /// Creates a new game profile.
 #[tauri::command]
 pub async fn profile__new_profile(
@@ -18,23 +62,23 @@ pub async fn profile__new_profile(
     profile: Profile,
 ) -> MaybeError<()> {
     // Save the profile.
-    if let Err(error) = profile.save().await {
-        warn!("Failed to save profile: {}", error);
-        return Err("launcher.error.profile.unknown");
+    let mut game_manager = GameManager::get().write().await;
+    let profile = match game_manager.save_profile(profile).await {
+        Ok(profile) => profile,
+        Err(error) => {
+            warn!("Failed to save profile: {}", error);
+            return Err("launcher.error.profile.unknown");
+        }
     };
-
-    // Lock the selected profile.
-    let mut selected_profile = state.0.lock().unwrap();
+    drop(game_manager);
 
     // Check if an existing profile is set.
-    let mut state = GLOBAL_STATE.write().unwrap();
-    if state.selected_profile.is_none() || selected_profile.is_none() {
-        // Set the persistent state.
-        state.selected_profile = Some(profile.id.clone());
-        state.save().ok();
-
-        // Set the selected profile.
-        *selected_profile = Some(profile);
+    let has_selected = GLOBAL_STATE.read().recover().selected_profile.is_some()
+        && state.0.lock().recover().is_some();
+
+    // If not, select the new profile.
+    if !has_selected {
+        state::select_profile(profile);
     }
 
     Ok(())
@@ -42,10 +86,7 @@ pub async fn profile__new_profile(
 
 /// Sets the selected profile by the given ID.
 #[tauri::command]
-pub async fn profile__set_profile(
-    state: State<'_, SelectedProfile>,
-    profile_id: String
-) -> MaybeError<()> {
+pub async fn profile__set_profile(profile_id: String) -> MaybeError<()> {
     // Get the game manager.
     let game_manager = GameManager::get().read().await;
 
@@ -55,14 +96,330 @@ pub async fn profile__set_profile(
         return Err("launcher.error.profile.bad-id");
     };
 
-    // Set the persisted state's selected profile.
-    let mut persisted_state = GLOBAL_STATE.write().unwrap();
-    persisted_state.selected_profile = Some(profile_id.clone());
-    persisted_state.save().ok();
+    // Set the selected profile.
+    state::select_profile(profile);
+
+    Ok(())
+}
+
+/// Sets the Wine prefix used to run the game of a profile.
+///
+/// If no prefix is given, Wine's default prefix is used.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn profile__set_wine_prefix(
+    profile_id: String,
+    prefix: Option<String>,
+) -> MaybeError<()> {
+    // Check that the prefix is a Wine prefix.
+    if let Some(prefix) = &prefix {
+        if !runners::is_prefix(prefix) {
+            return Err("launcher.error.wine.bad-prefix");
+        }
+    }
+
+    let mut game_manager = GameManager::get().write().await;
+    let Some(mut profile) = game_manager.get_profile(&profile_id) else {
+        return Err("launcher.error.profile.bad-id");
+    };
+
+    // Save the profile with the new prefix.
+    profile.wine_prefix = prefix;
+    let profile = match game_manager.save_profile(profile).await {
+        Ok(profile) => profile,
+        Err(error) => {
+            warn!("Failed to save profile: {}", error);
+            return Err("launcher.error.profile.unknown");
+        }
+    };
+
+    // Update the selected profile, if it was changed.
+    state::refresh_selected(profile);
+
+    Ok(())
+}
+
+/// Sets the Wine DLL overrides used to run the game of a profile.
+///
+/// This maps DLL names to their load order, such as `n,b`.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn profile__set_dll_overrides(
+    profile_id: String,
+    overrides: BTreeMap<String, String>,
+) -> MaybeError<()> {
+    // Check that every override is valid.
+    if !overrides
+        .iter()
+        .all(|(name, order)| runners::is_dll_override(name, order))
+    {
+        return Err("launcher.error.wine.bad-dll-override");
+    }
+
+    let mut game_manager = GameManager::get().write().await;
+    let Some(mut profile) = game_manager.get_profile(&profile_id) else {
+        return Err("launcher.error.profile.bad-id");
+    };
+
+    // Save the profile with the new overrides.
+    profile.wine_dll_overrides = overrides;
+    let profile = match game_manager.save_profile(profile).await {
+        Ok(profile) => profile,
+        Err(error) => {
+            warn!("Failed to save profile: {}", error);
+            return Err("launcher.error.profile.unknown");
+        }
+    };
+
+    // Update the selected profile, if it was changed.
+    state::refresh_selected(profile);
+
+    Ok(())
+}
+
+/// Fetches the arguments passed to the game of a profile.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn profile__get_launch_args(profile_id: String) -> MaybeError<Vec<String>> {
+    let game_manager = GameManager::get().read().await;
+    let Some(profile) = game_manager.get_profile(&profile_id) else {
+        return Err("launcher.error.profile.bad-id");
+    };
+
+    Ok(profile.launch_args)
+}
+
+/// Sets the arguments passed to the game of a profile.
+///
+/// Each argument is passed as-is, and is quoted when the command line is built.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn profile__set_launch_args(
+    profile_id: String,
+    launch_args: Vec<String>,
+) -> MaybeError<()> {
+    let mut game_manager = GameManager::get().write().await;
+    let Some(mut profile) = game_manager.get_profile(&profile_id) else {
+        return Err("launcher.error.profile.bad-id");
+    };
+
+    // Save the profile with the new arguments.
+    profile.launch_args = launch_args;
+    let profile = match game_manager.save_profile(profile).await {
+        Ok(profile) => profile,
+        Err(error) => {
+            warn!("Failed to save profile: {}", error);
+            return Err("launcher.error.profile.unknown");
+        }
+    };
 
-    // Set the app instance's selected profile.
-    let mut selected_profile = state.0.lock().unwrap();
-    *selected_profile = Some(profile);
+    // Update the selected profile, if it was changed.
+    state::refresh_selected(profile);
 
     Ok(())
 }
+
+/// Exports a single profile to a JSON file.
+///
+/// Referenced tools and mods are described, but their files aren't included.
+#[tauri::command]
+pub async fn profile__export(profile_id: String, path: String) -> MaybeError<()> {
+    // Fetch the profile by its ID.
+    let game_manager = GameManager::get().read().await;
+    let Some(profile) = game_manager.get_profile(&profile_id) else {
+        return Err("launcher.error.profile.bad-id");
+    };
+    drop(game_manager);
+
+    // Serialize the profile descriptor.
+    let descriptor = ProfileDescriptor {
+        format: DESCRIPTOR_FORMAT,
+        profile,
+    };
+    let Ok(data) = serde_json::to_string_pretty(&descriptor) else {
+        return Err("launcher.error.profile.export-failed");
+    };
+
+    // Write the descriptor to the file.
+    if let Err(error) = fs::write(&path, data) {
+        warn!("Failed to export profile to '{}': {}", path, error);
+        return Err("launcher.error.profile.export-failed");
+    }
+
+    Ok(())
+}
+
+/// Reads a profile descriptor from a JSON file.
+fn read_descriptor(path: &str) -> anyhow::Result<ProfileDescriptor> {
+    let data = fs::read(path)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+/// Imports a profile from a JSON file created by `profile__export`.
+///
+/// Referenced tools and mods are matched to registered ones by their ID.\
+/// Any which aren't registered are returned, so the user can relink them.\
+/// If the profile's ID is already in use, a new one is generated.
+#[tauri::command]
+pub async fn profile__import(path: String) -> MaybeError<ImportedProfile> {
+    // Read the profile descriptor.
+    let descriptor = match read_descriptor(&path) {
+        Ok(descriptor) => descriptor,
+        Err(error) => {
+            warn!("Failed to read profile from '{}': {}", path, error);
+            return Err("launcher.error.profile.import-failed");
+        }
+    };
+
+    // Check if the descriptor format is supported.
+    if descriptor.format > DESCRIPTOR_FORMAT {
+        return Err("launcher.error.profile.import-failed");
+    }
+
+    let mut game_manager = GameManager::get().write().await;
+    let mut profile = descriptor.profile;
+
+    // Use the locally installed game version.
+    let Some(version) = game_manager
+        .versions
+        .iter()
+        .find(|version| version.version == profile.version.version)
+    else {
+        return Err("launcher.error.profile.bad-version");
+    };
+    profile.version = version.clone();
+
+    // Link the referenced tools to registered tools.
+    let (tools, missing_tools): (Vec<_>, Vec<_>) = profile
+        .tools
+        .into_iter()
+        .partition(|tool| game_manager.tools.iter().any(|t| t.id == tool.id));
+    profile.tools = tools
+        .into_iter()
+        .filter_map(|tool| game_manager.tools.iter().find(|t| t.id == tool.id).cloned())
+        .collect();
+
+    // Link the referenced mods to registered mods.
+    let (mods, missing_mods): (Vec<_>, Vec<_>) = profile
+        .mods
+        .into_iter()
+        .partition(|r#mod| game_manager.mods.iter().any(|m| m.id == r#mod.id));
+    profile.mods = mods
+        .into_iter()
+        .filter_map(|r#mod| game_manager.mods.iter().find(|m| m.id == r#mod.id).cloned())
+        .collect();
+
+    // Mark the profile as untrusted.
+    profile.imported = true;
+
+    // Wine prefixes are local to the exporting machine.
+    profile.wine_prefix = None;
+
+    // DLL overrides change what the game loads, so they aren't taken from untrusted profiles.
+    // The overrides for linked DLL tools are still derived when the game is run.
+    profile.wine_dll_overrides.clear();
+
+    // Generate a new ID if the profile's ID is taken.
+    if profile.id.is_empty() || game_manager.get_profile(&profile.id).is_some() {
+        profile.id = utils::random_id();
+    }
+
+    // Save the profile.
+    let profile = match game_manager.save_profile(profile).await {
+        Ok(profile) => profile,
+        Err(error) => {
+            warn!("Failed to save imported profile: {}", error);
+            return Err("launcher.error.profile.unknown");
+        }
+    };
+
+    Ok(ImportedProfile {
+        profile,
+        missing_tools,
+        missing_mods,
+    })
+}
+
+/// The path of a profile's tool or mod, and what it resolves to.
+#[derive(Serialize, Debug)]
+pub struct ResolvedPath {
+    /// The ID of the tool or mod.
+    pub id: String,
+
+    /// Whether this is a `tool` or a `mod`.
+    pub kind: &'static str,
+
+    /// The name of the tool or mod.
+    pub name: String,
+
+    /// The path, as it is stored.
+    pub path: String,
+
+    /// The absolute path, if it could be resolved.
+    pub resolved: Option<String>,
+
+    /// Whether the resolved path exists.
+    pub exists: bool,
+
+    /// Whether the file has a recognized extension. (`.dll` or `.exe`)
+    pub recognized: bool,
+}
+
+impl ResolvedPath {
+    /// Resolves the path of a tool or mod.
+    fn new(id: &str, kind: &'static str, name: &str, path: &str) -> Self {
+        let resolved = system::resolve_path(path).ok();
+        let recognized = resolved
+            .as_ref()
+            .and_then(|path| path.extension())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("dll") || ext.eq_ignore_ascii_case("exe"));
+
+        ResolvedPath {
+            id: id.to_string(),
+            kind,
+            name: name.to_string(),
+            path: path.to_string(),
+            exists: resolved.as_ref().is_some_and(|path| path.exists()),
+            resolved: resolved.map(|path| path.to_string_lossy().to_string()),
+            recognized,
+        }
+    }
+}
+
+/// Resolves the paths of a profile's tools and mods.
+///
+/// This shows what aliases such as `$APPDATA` expand to, and whether the files exist.
+#[tauri::command]
+pub async fn profile__resolved_paths(profile_id: String) -> MaybeError<Vec<ResolvedPath>> {
+    let game_manager = GameManager::get().read().await;
+    let Some(profile) = game_manager.get_profile(&profile_id) else {
+        return Err("launcher.error.profile.bad-id");
+    };
+
+    let tools = profile
+        .tools
+        .iter()
+        .map(|tool| ResolvedPath::new(&tool.id, "tool", &tool.name, &tool.path));
+    let mods = profile
+        .mods
+        .iter()
+        .map(|r#mod| ResolvedPath::new(&r#mod.id, "mod", &r#mod.name, &r#mod.path));
+
+    Ok(tools.chain(mods).collect())
+}
@@ -0,0 +1,380 @@
+use crate::app::game::Profile;
+use crate::config::{save_config, Config};
+use crate::system;
+use crate::utils::MaybeError;
+use log::warn;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The kind of compatibility layer used to run the game.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum RunnerKind {
+    Wine,
+    Proton,
+}
+
+/// An installed compatibility layer which can run the game.
+#[derive(Serialize, Debug, Clone)]
+pub struct Runner {
+    pub kind: RunnerKind,
+
+    /// The name of the runner, such as the Proton version.
+    pub name: String,
+
+    /// The path to the runner's executable.
+    pub path: String,
+}
+
+/// Detects installed Wine and Proton runners.
+///
+/// # On Windows
+///
+/// The game runs natively, so this returns nothing.
+///
+/// # On Linux/macOS
+///
+/// This scans `PATH`, Steam libraries, and Lutris for runners.
+#[tauri::command]
+pub fn launcher__detect_runners() -> Vec<Runner> {
+    if cfg!(windows) {
+        return Vec::new();
+    }
+
+    let mut runners = Vec::new();
+
+    // Find Wine on the `PATH`.
+    let paths = std::env::var_os("PATH").unwrap_or_default();
+    for dir in std::env::split_paths(&paths) {
+        for binary in ["wine", "wine64"] {
+            add_runner(&mut runners, RunnerKind::Wine, binary, dir.join(binary));
+        }
+    }
+
+    let Some(home) = dirs::home_dir() else {
+        return runners;
+    };
+
+    // Find Proton in Steam libraries and custom compatibility tools.
+    let proton_dirs = [
+        ".steam/steam/steamapps/common",
+        ".local/share/Steam/steamapps/common",
+        ".steam/root/compatibilitytools.d",
+        ".local/share/Steam/compatibilitytools.d",
+    ];
+    for dir in proton_dirs {
+        for (name, path) in subdirectories(&home.join(dir)) {
+            add_runner(&mut runners, RunnerKind::Proton, &name, path.join("proton"));
+        }
+    }
+
+    // Find Wine builds installed by Lutris.
+    for (name, path) in subdirectories(&home.join(".local/share/lutris/runners/wine")) {
+        add_runner(&mut runners, RunnerKind::Wine, &name, path.join("bin/wine"));
+    }
+
+    runners
+}
+
+/// Returns the runner used to run the game.
+///
+/// This is the configured runner, or the first detected one.
+pub fn runner() -> MaybeError<Runner> {
+    if let Some(path) = Config::launcher().wine.runner {
+        if !system::resolve_path(&path).is_ok_and(|path| path.is_file()) {
+            return Err("launcher.error.wine.no-runner");
+        }
+
+        // Proton is run through its `proton` script.
+        let kind = match Path::new(&path).file_name() {
+            Some(name) if name == "proton" => RunnerKind::Proton,
+            _ => RunnerKind::Wine,
+        };
+
+        return Ok(Runner {
+            kind,
+            name: runner_name(&path),
+            path,
+        });
+    }
+
+    launcher__detect_runners()
+        .into_iter()
+        .next()
+        .ok_or("launcher.error.wine.no-runner")
+}
+
+/// Returns the name of a runner from its path.
+fn runner_name(path: &str) -> String {
+    Path::new(path)
+        .parent()
+        .and_then(|dir| dir.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// Creates a command which runs a program with the runner.
+///
+/// If a prefix is given, the program runs in it.
+pub fn wine_command(runner: &Runner, prefix: Option<&Path>) -> Command {
+    let mut command = Command::new(&runner.path);
+    if runner.kind == RunnerKind::Proton {
+        command.arg("run");
+
+        // Proton expects to be run by Steam.
+        if let Some(steam_dir) = dirs::home_dir().map(|home| home.join(".steam/steam")) {
+            command.env("STEAM_COMPAT_CLIENT_INSTALL_PATH", steam_dir);
+        }
+    }
+
+    if let Some(prefix) = prefix {
+        match runner.kind {
+            RunnerKind::Wine => command.env("WINEPREFIX", prefix),
+            RunnerKind::Proton => command.env("STEAM_COMPAT_DATA_PATH", prefix),
+        };
+    }
+
+    command
+}
+
+/// Creates the command which runs the game of the profile.
+///
+/// # On Windows
+///
+/// The game runs natively.
+///
+/// # On Linux/macOS
+///
+/// The game runs with the runner, in the profile's Wine prefix if it has one.
+pub fn game_command(profile: &Profile) -> MaybeError<Command> {
+    #[cfg(windows)]
+    let mut command = {
+        let executable = system::canonicalize(&profile.version.path)
+            .unwrap_or_else(|_| profile.version.path.clone());
+
+        let mut command = Command::new(executable);
+        command.args(&profile.launch_args);
+        command
+    };
+
+    #[cfg(unix)]
+    let mut command = {
+        let runner = runner()?;
+
+        // Check that the profile's prefix still exists.
+        let prefix = match &profile.wine_prefix {
+            Some(prefix) if is_prefix(prefix) => system::resolve_path(prefix).ok(),
+            Some(_) => return Err("launcher.error.wine.bad-prefix"),
+            None => None,
+        };
+
+        let mut command = wine_command(&runner, prefix.as_deref());
+        command
+            .arg(&profile.version.path)
+            .args(&profile.launch_args);
+
+        // Let Wine load the profile's DLLs instead of its own.
+        let dll_overrides = dll_overrides(profile);
+        if !dll_overrides.is_empty() {
+            command.env("WINEDLLOVERRIDES", format_dll_overrides(&dll_overrides));
+        }
+
+        command
+    };
+
+    // The game expects to be run from its directory.
+    if let Some(game_dir) = Path::new(&profile.version.path).parent() {
+        command.current_dir(game_dir);
+    }
+
+    Ok(command)
+}
+
+/// Checks if a Wine DLL override is valid.
+///
+/// The name can't contain separators, and the load order must be
+/// native (`n`), builtin (`b`), both, or empty to disable the DLL.
+pub fn is_dll_override(name: &str, order: &str) -> bool {
+    let valid_name = !name.is_empty()
+        && !name
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '=' | ';' | ','));
+
+    valid_name && matches!(order, "" | "n" | "b" | "n,b" | "b,n")
+}
+
+/// Parses DLL overrides in the `WINEDLLOVERRIDES` format. (e.g. `dxgi=n,b;d3d11=n`)
+///
+/// Invalid overrides are skipped.
+pub fn parse_dll_overrides(overrides: &str) -> BTreeMap<String, String> {
+    overrides
+        .split(';')
+        .filter_map(|entry| entry.split_once('='))
+        .map(|(name, order)| (name.trim().to_string(), order.trim().to_string()))
+        .filter(|(name, order)| is_dll_override(name, order))
+        .collect()
+}
+
+/// Formats DLL overrides in the `WINEDLLOVERRIDES` format.
+pub fn format_dll_overrides(overrides: &BTreeMap<String, String>) -> String {
+    overrides
+        .iter()
+        .map(|(name, order)| format!("{}={}", name, order))
+        .collect::<Vec<String>>()
+        .join(";")
+}
+
+/// Returns the DLL overrides used to run the game of the profile.
+///
+/// DLL tools are loaded natively by default, so the game picks them up from its directory.\
+/// The profile's own overrides take precedence.
+#[cfg(unix)]
+pub fn dll_overrides(profile: &Profile) -> BTreeMap<String, String> {
+    let mut overrides: BTreeMap<String, String> = profile
+        .tools
+        .iter()
+        .map(|tool| Path::new(&tool.path))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "dll"))
+        .filter_map(|path| path.file_stem())
+        .map(|name| (name.to_string_lossy().to_lowercase(), "n,b".to_string()))
+        .collect();
+
+    overrides.extend(profile.wine_dll_overrides.clone());
+    overrides
+}
+
+/// A Wine prefix known to the launcher.
+#[derive(Serialize, Debug)]
+pub struct WinePrefix {
+    pub path: String,
+
+    /// Whether the directory is an initialized Wine prefix.
+    pub valid: bool,
+}
+
+/// Checks if the directory is an initialized Wine prefix.
+///
+/// Initialized prefixes contain the system registry and a `C:` drive.\
+/// Proton keeps the prefix in a `pfx` directory, which is also checked.
+pub fn is_prefix(path: &str) -> bool {
+    let Ok(path) = system::resolve_path(path) else {
+        return false;
+    };
+
+    [path.clone(), path.join("pfx")]
+        .iter()
+        .any(|dir| dir.join("system.reg").is_file() && dir.join("drive_c").is_dir())
+}
+
+/// Lists the Wine prefixes known to the launcher.
+#[tauri::command]
+pub fn wine__list_prefixes() -> Vec<WinePrefix> {
+    Config::launcher()
+        .wine
+        .prefixes
+        .into_iter()
+        .map(|path| WinePrefix {
+            valid: is_prefix(&path),
+            path,
+        })
+        .collect()
+}
+
+/// Creates a new Wine prefix, then adds it to the known prefixes.
+///
+/// This runs `wineboot` with the runner to initialize the prefix.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub async fn wine__create_prefix(path: String) -> MaybeError<()> {
+    let runner = runner()?;
+
+    // Create the prefix directory.
+    let Ok(prefix) = system::resolve_path(&path) else {
+        return Err("launcher.error.wine.bad-prefix");
+    };
+    if let Err(error) = std::fs::create_dir_all(&prefix) {
+        warn!("Failed to create the Wine prefix directory: {}", error);
+        return Err("launcher.error.wine.create-failed");
+    }
+
+    // Initialize the prefix with `wineboot`.
+    let output = tauri::async_runtime::spawn_blocking(move || {
+        wine_command(&runner, Some(&prefix))
+            .args(["wineboot", "--init"])
+            .output()
+    })
+    .await;
+
+    match output {
+        Ok(Ok(output)) if output.status.success() => (),
+        Ok(Ok(output)) => {
+            let error = String::from_utf8_lossy(&output.stderr);
+            warn!("Failed to initialize the Wine prefix: {}", error.trim());
+            return Err("launcher.error.wine.create-failed");
+        }
+        Ok(Err(error)) => {
+            warn!("Failed to run wineboot: {}", error);
+            return Err("launcher.error.wine.create-failed");
+        }
+        Err(error) => {
+            warn!("Failed to run wineboot: {}", error);
+            return Err("launcher.error.wine.create-failed");
+        }
+    }
+
+    // Add the prefix to the configuration.
+    let mut config = Config::get();
+    if !config.launcher.wine.prefixes.contains(&path) {
+        config.launcher.wine.prefixes.push(path);
+
+        if let Err(error) = save_config(&config) {
+            warn!("Failed to save the configuration: {}", error);
+            return Err("backend.config.save-failed");
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds a runner, if its executable exists and it wasn't already found.
+fn add_runner(runners: &mut Vec<Runner>, kind: RunnerKind, name: &str, path: PathBuf) {
+    if !path.is_file() {
+        return;
+    }
+
+    // Symbolic links can lead to the same runner.
+    let path = path.canonicalize().unwrap_or(path);
+    let path = path.to_string_lossy().to_string();
+    if runners.iter().any(|runner| runner.path == path) {
+        return;
+    }
+
+    runners.push(Runner {
+        kind,
+        name: name.to_string(),
+        path,
+    });
+}
+
+/// Lists the names and paths of the directories in the given directory.
+fn subdirectories(dir: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .filter_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            Some((name, path))
+        })
+        .collect()
+}
@@ -1,39 +1,196 @@
 use std::sync::{LazyLock, Mutex};
-use std::time::Instant;
-use tauri::{AppHandle, WebviewUrl, WebviewWindowBuilder};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+use tokio::sync::mpsc::error::TryRecvError;
+use tokio::task::JoinHandle;
 use crate::app::game;
 use crate::capabilities::sniffer;
 use crate::capabilities::sniffer::VisualPacket;
+use crate::config::{save_config, Config};
 use crate::events;
 use crate::events::Event;
+use crate::utils::{MaybeError, Recover};
 
 /// This value holds whether the GUI-based sniffer is running or not.
 static SNIFFER_RUNNING: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
 
+/// This holds the shutdown hook of the GUI-based sniffer, if it is running.
+static SHUTDOWN_HOOK: LazyLock<Mutex<Option<crossbeam_channel::Sender<()>>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// This holds the task forwarding packets to the webview, if the sniffer is running.
+///
+/// The task finishes once the sniffer has closed the capture.
+static FORWARDER: LazyLock<Mutex<Option<JoinHandle<()>>>> = LazyLock::new(|| Mutex::new(None));
+
+/// This holds the task stopping the sniffer when the game closes, if the sniffer is running.
+static STATUS_LISTENER: LazyLock<Mutex<Option<JoinHandle<()>>>> =
+    LazyLock::new(|| Mutex::new(None));
+
+/// How long to wait for the sniffer to close the capture.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Checks if the GUI-based sniffer is running.
+pub fn is_running() -> bool {
+    *SNIFFER_RUNNING.lock().recover()
+}
+
+/// Marks the sniffer as running, if it isn't already.
+///
+/// This stops the GUI-based sniffer from starting while another capture uses the device.\
+/// The sniffer is marked as stopped again once the guard is dropped.
+pub fn claim_running() -> Option<RunningGuard> {
+    let mut running = SNIFFER_RUNNING.lock().recover();
+    if *running {
+        return None;
+    }
+
+    *running = true;
+    Some(RunningGuard)
+}
+
+/// Marks the sniffer as stopped once dropped.
+pub struct RunningGuard;
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        *SNIFFER_RUNNING.lock().recover() = false;
+    }
+}
+
+/// Checks if a packet visualizer is listening for captured packets.
+fn visualizer_subscribed() -> bool {
+    Event::VisualizerPacket(VisualPacket::default()).has_subscribers()
+}
+
+/// Stops the GUI-based sniffer, if it is running.
+///
+/// This is safe to call multiple times.
+pub fn stop_sniffer() {
+    // Take the shutdown hook so it is only ever called once.
+    let Some(shutdown_hook) = SHUTDOWN_HOOK.lock().recover().take() else {
+        return;
+    };
+
+    // Call the shutdown hook.
+    if let Err(err) = shutdown_hook.send(()) {
+        warn!("Failed to send shutdown signal: {}", err);
+    }
+
+    // Unset the sniffer value.
+    *SNIFFER_RUNNING.lock().recover() = false;
+}
+
+/// Stops the GUI-based sniffer, and waits for it to close the capture.
+///
+/// The buffered packets are then saved as a dump, so they aren't lost.\
+/// This should be called before the application exits.
+pub async fn shutdown_sniffer() {
+    stop_sniffer();
+    if !join_tasks().await {
+        return;
+    }
+
+    // Save the packets which were captured.
+    match sniffer::save_buffer() {
+        Ok(Some(path)) => info!("Buffered packets have been dumped to {}.", path.display()),
+        Ok(None) => {}
+        Err(error) => warn!("Failed to save the buffered packets: {:#?}", error),
+    }
+}
+
+/// Waits for the tasks of a stopped sniffer to finish.
+///
+/// The forwarding task finishes once the capture is closed.\
+/// If it takes longer than `SHUTDOWN_TIMEOUT`, it is aborted instead.
+///
+/// Returns `false` if there was no forwarding task to wait for.
+async fn join_tasks() -> bool {
+    // The status listener only stops the sniffer, so it isn't needed anymore.
+    if let Some(status_listener) = STATUS_LISTENER.lock().recover().take() {
+        status_listener.abort();
+    }
+
+    let Some(forwarder) = FORWARDER.lock().recover().take() else {
+        return false;
+    };
+
+    // A stale task could otherwise change the state of the next capture.
+    let abort_handle = forwarder.abort_handle();
+    if let Err(error) = tokio::time::timeout(SHUTDOWN_TIMEOUT, forwarder).await {
+        warn!("The sniffer didn't stop in time: {}", error);
+        abort_handle.abort();
+    }
+
+    true
+}
+
+/// Starts the GUI-based sniffer and opens the packet visualizer.
+///
+/// This is used to start sniffing when the game opens.\
+/// If the desktop application isn't running, or the sniffer is already running, this does nothing.
+pub fn start_with_game() {
+    let Some(app_handle) = events::app_handle().cloned() else {
+        return;
+    };
+    if is_running() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = sniffer__run(app_handle.clone()).await {
+            warn!("Failed to start the sniffer with the game: {}", error);
+            return;
+        }
+        events::emit_event(&app_handle, Event::SnifferStatusChanged(true));
+
+        if let Err(error) = sniffer__open(app_handle).await {
+            warn!("Failed to open the packet visualizer: {}", error);
+        }
+    });
+}
+
 /// Runs the packet sniffer asynchronously.
 ///
-/// The sniffer will stop when the game is no longer detected.
+/// The sniffer will stop when the game is no longer detected.\
+/// If `sniffer.open-visualizer` is enabled, the packet visualizer is opened too.
 #[tauri::command]
 pub async fn sniffer__run(app_handle: AppHandle) -> Result<(), &'static str> {
     // If the sniffer is running, return early.
-    if *SNIFFER_RUNNING.lock().unwrap() {
+    if *SNIFFER_RUNNING.lock().recover() {
         return Ok(());
     }
 
+    // Check if packets can be captured.
+    sniffer::check_capture()?;
+
     // Get the status listener.
     let mut listener = game::new_status_listener();
 
     // Run the sniffer itself.
-    let (mut rx, shutdown_hook) = match sniffer::run_sniffer().await {
+    let (mut rx, shutdown_hook) = match sniffer::run_sniffer(None).await {
         Ok((rx, hook)) => (rx, hook),
         Err(_) => return Err("capability.sniffer.error")
     };
 
+    // Store the shutdown hook so the sniffer can be stopped from elsewhere.
+    *SHUTDOWN_HOOK.lock().recover() = Some(shutdown_hook);
+
+    // Packets from a previous capture aren't part of this one.
+    sniffer::reset_buffer();
+
     // Create a thread for sending messages to the webview.
     let wv_listener = listener.clone();
+    let window_handle = app_handle.clone();
+
+    // Check if only the packets' metadata should be kept.
+    let config = Config::sniffer();
+    let metadata_only = config.metadata_only;
+    let buffer_capacity = config.buffer_capacity;
 
-    tokio::spawn(async move {
+    let forwarder = tokio::spawn(async move {
         let start_time = Instant::now();
+        sniffer::set_capture_start(Some(start_time));
 
         loop {
             // Check if the status has changed.
@@ -48,17 +205,34 @@ pub async fn sniffer__run(app_handle: AppHandle) -> Result<(), &'static str> {
             }
 
             // Check if a packet is available.
-            if let Ok(packet) = rx.try_recv() {
-                // If so, push it to the webview through an event.
-                let packet = VisualPacket::into_game(&packet, start_time);
-                let event = Event::VisualizerPacket(packet);
-                events::emit_event(&app_handle, event);
+            match rx.try_recv() {
+                Ok(packet) => {
+                    // Only keep and emit the packet if a visualizer is open.
+                    if !visualizer_subscribed() {
+                        continue;
+                    }
+
+                    // If so, push it to the webview through an event.
+                    let mut packet = VisualPacket::into_game(&packet, start_time);
+                    if metadata_only {
+                        packet = packet.into_metadata();
+                    }
+                    let packet = sniffer::buffer_packet(packet, buffer_capacity);
+                    events::emit_event(&app_handle, Event::VisualizerPacket(packet));
+                }
+                // If the sniffer was stopped, stop forwarding packets.
+                Err(TryRecvError::Disconnected) => break,
+                // Otherwise, let other tasks run, so this task can be stopped.
+                Err(TryRecvError::Empty) => tokio::task::yield_now().await,
             }
         }
+
+        sniffer::set_capture_start(None);
     });
+    *FORWARDER.lock().recover() = Some(forwarder);
 
     // Listen for the listener.
-    tokio::spawn(async move {
+    let status_listener = tokio::spawn(async move {
         loop {
             let _ = listener.changed().await;
 
@@ -69,26 +243,81 @@ pub async fn sniffer__run(app_handle: AppHandle) -> Result<(), &'static str> {
             }
         }
 
-        // Call the shutdown hook.
-        if let Err(err) = shutdown_hook.send(()) {
-            warn!("Failed to send shutdown signal: {}", err);
-        }
-
-        // Unset the sniffer value.
-        *SNIFFER_RUNNING.lock().unwrap() = false;
+        // Stop the sniffer.
+        stop_sniffer();
     });
+    *STATUS_LISTENER.lock().recover() = Some(status_listener);
 
     // Set the sniffer value.
-    *SNIFFER_RUNNING.lock().unwrap() = true;
+    *SNIFFER_RUNNING.lock().recover() = true;
+
+    // Open the visualizer, if configured.
+    if Config::sniffer().open_visualizer {
+        if let Err(error) = sniffer__open(window_handle).await {
+            warn!("Failed to open the packet visualizer: {}", error);
+        }
+    }
+
+    Ok(())
+}
+
+/// Sets the network device used by the sniffer.
+///
+/// If the sniffer is running, it will be restarted on the new device.
+#[tauri::command]
+pub async fn sniffer__set_device(app_handle: AppHandle, name: String) -> MaybeError<()> {
+    // Check if the device exists.
+    if !sniffer::device_exists(&name) {
+        return Err("capability.sniffer.error.bad-device");
+    }
+
+    // Write the device to the configuration.
+    {
+        let mut config = Config::get();
+        config.sniffer.device_name = name;
+
+        if let Err(error) = save_config(&config) {
+            warn!("Failed to save the configuration: {}", error);
+            return Err("backend.config.save-failed");
+        }
+    }
+
+    // If the sniffer isn't running, there's nothing to restart.
+    // A diagnostic capture marks the sniffer as running too, but has no shutdown hook.
+    let running = SHUTDOWN_HOOK.lock().recover().is_some();
+    if !running {
+        return Ok(());
+    }
+
+    // Restart the sniffer on the new device.
+    // The previous capture has to finish first, so it can't change the new capture's state.
+    events::emit_event(&app_handle, Event::SnifferStatusChanged(false));
+    stop_sniffer();
+    join_tasks().await;
+
+    sniffer__run(app_handle.clone()).await?;
+    events::emit_event(&app_handle, Event::SnifferStatusChanged(true));
 
     Ok(())
 }
 
 /// Opens the packet visualizer.
 ///
-/// This opens a new webview window.
+/// This opens a new webview window.\
+/// If the visualizer is already open, it is focused instead.
 #[tauri::command]
 pub async fn sniffer__open(app_handle: AppHandle) -> Result<(), &'static str> {
+    // Focus the existing window, if there is one.
+    if let Some(window) = app_handle.get_webview_window("visualizer") {
+        window.show()
+            .map_err(|_| "Failed to show visualizer window.")?;
+        if let Err(err) = window.set_focus() {
+            warn!("Failed to focus visualizer window: {}", err);
+        }
+
+        return Ok(());
+    }
+
     // Create the webview window.
     let window = WebviewWindowBuilder::new(
         &app_handle, "visualizer",
@@ -107,3 +336,28 @@ pub async fn sniffer__open(app_handle: AppHandle) -> Result<(), &'static str> {
 
     Ok(())
 }
+
+/// Closes the packet visualizer.
+///
+/// If `sniffer.stop-with-visualizer` is enabled, the sniffer is stopped too.\
+/// If the visualizer isn't open, this does nothing.
+#[tauri::command]
+pub async fn sniffer__close_visualizer(app_handle: AppHandle) -> Result<(), &'static str> {
+    let Some(window) = app_handle.get_webview_window("visualizer") else {
+        return Ok(());
+    };
+
+    window.close()
+        .map_err(|err| {
+            warn!("Failed to close visualizer window: {}", err);
+            "Failed to close visualizer window."
+        })?;
+
+    // Stop the sniffer, if configured.
+    if Config::sniffer().stop_with_visualizer && is_running() {
+        stop_sniffer();
+        events::emit_event(&app_handle, Event::SnifferStatusChanged(false));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use crate::capabilities::sniffer;
+use crate::utils::MaybeError;
+use serde::Serialize;
+use serde_json::Value;
+
+/// A byte which differs between two packets.
+#[derive(Debug, Clone, Serialize)]
+pub struct ByteDiff {
+    /// The offset of the byte in the packets' data.
+    pub offset: usize,
+
+    /// The byte in the first packet.
+    ///
+    /// This is `None` if the byte was added in the second packet.
+    pub a: Option<u8>,
+
+    /// The byte in the second packet.
+    ///
+    /// This is `None` if the byte was removed in the second packet.
+    pub b: Option<u8>,
+}
+
+/// A decoded field which differs between two packets.
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldDiff {
+    /// The path to the field, separated by dots.
+    ///
+    /// Array elements are referenced by their index.
+    pub path: String,
+
+    /// The value in the first packet.
+    ///
+    /// This is `None` if the field was added in the second packet.
+    pub a: Option<Value>,
+
+    /// The value in the second packet.
+    ///
+    /// This is `None` if the field was removed in the second packet.
+    pub b: Option<Value>,
+}
+
+/// The differences between two packets.
+#[derive(Debug, Clone, Serialize)]
+pub struct PacketDiff {
+    /// The bytes which differ between the packets' raw data.
+    pub bytes: Vec<ByteDiff>,
+
+    /// The fields which differ between the packets' decoded data.
+    ///
+    /// This is `None` if either packet couldn't be decoded.
+    pub fields: Option<Vec<FieldDiff>>,
+}
+
+/// Compares the raw data of two packets byte-by-byte.
+///
+/// Only differing bytes are returned.\
+/// If the lengths differ, the trailing bytes are marked as added or removed.
+pub fn diff_bytes(a: &[u8], b: &[u8]) -> Vec<ByteDiff> {
+    (0..a.len().max(b.len()))
+        .filter_map(|offset| {
+            let (a, b) = (a.get(offset).copied(), b.get(offset).copied());
+            (a != b).then_some(ByteDiff { offset, a, b })
+        })
+        .collect()
+}
+
+/// Compares two decoded JSON values field-by-field.
+///
+/// Only differing fields are returned.
+pub fn diff_fields(a: &Value, b: &Value) -> Vec<FieldDiff> {
+    let mut diffs = Vec::new();
+    walk_diff(String::new(), Some(a), Some(b), &mut diffs);
+
+    diffs
+}
+
+/// Recursively compares two JSON values, writing the differences to `diffs`.
+fn walk_diff(path: String, a: Option<&Value>, b: Option<&Value>, diffs: &mut Vec<FieldDiff>) {
+    match (a, b) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            // Compare every key from both objects.
+            let keys = a.keys().chain(b.keys().filter(|key| !a.contains_key(*key)));
+            for key in keys {
+                walk_diff(join_path(&path, key), a.get(key), b.get(key), diffs);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for index in 0..a.len().max(b.len()) {
+                let path = join_path(&path, &index.to_string());
+                walk_diff(path, a.get(index), b.get(index), diffs);
+            }
+        }
+        (a, b) if a != b => diffs.push(FieldDiff {
+            path,
+            a: a.cloned(),
+            b: b.cloned(),
+        }),
+        _ => (),
+    }
+}
+
+/// Appends a key to a dot-separated field path.
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Compares two buffered packets.
+///
+/// The raw data is always compared.\
+/// The decoded data is only compared if both packets were decoded.
+#[tauri::command]
+pub fn sniffer__diff(index_a: u32, index_b: u32) -> MaybeError<PacketDiff> {
+    let (Some(a), Some(b)) = (
+        sniffer::buffered_packet(index_a),
+        sniffer::buffered_packet(index_b),
+    ) else {
+        return Err("capability.sniffer.error.bad-index");
+    };
+
+    // Packets which failed to decode have no data.
+    let decoded_a = serde_json::from_str::<Value>(&a.data).ok();
+    let decoded_b = serde_json::from_str::<Value>(&b.data).ok();
+
+    Ok(PacketDiff {
+        bytes: diff_bytes(&a.binary, &b.binary),
+        fields: decoded_a.zip(decoded_b).map(|(a, b)| diff_fields(&a, &b)),
+    })
+}
+
+/// Parses a hex byte pattern.
+///
+/// Whitespace is ignored, and `??` matches any byte.\
+/// Returns `None` if the pattern is empty or invalid.
+pub fn parse_pattern<S: AsRef<str>>(pattern: S) -> Option<Vec<Option<u8>>> {
+    let digits = pattern
+        .as_ref()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    if digits.is_empty() || digits.len() % 2 != 0 {
+        return None;
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| match pair {
+            ['?', '?'] => Some(None),
+            [high, low] => {
+                let byte = format!("{}{}", high, low);
+                u8::from_str_radix(&byte, 16).ok().map(Some)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Checks if the data contains the given byte pattern.
+pub fn contains_pattern(data: &[u8], pattern: &[Option<u8>]) -> bool {
+    data.windows(pattern.len()).any(|window| {
+        window
+            .iter()
+            .zip(pattern)
+            .all(|(byte, expected)| expected.is_none_or(|expected| *byte == expected))
+    })
+}
+
+/// Resolves a dot-separated field path in a JSON value.
+///
+/// Array elements are referenced by their index.
+pub fn resolve_field<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|key| !key.is_empty())
+        .try_fold(value, |value, key| match value {
+            Value::Object(object) => object.get(key),
+            Value::Array(array) => array.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+/// Removes the field at a dot-separated path from a JSON value.
+///
+/// Returns the removed value, if the field existed.
+pub fn remove_field(value: &mut Value, path: &str) -> Option<Value> {
+    let (parent, key) = path.rsplit_once('.').unwrap_or(("", path));
+
+    // Walk to the parent of the field.
+    let parent = parent.split('.').filter(|key| !key.is_empty()).try_fold(
+        value,
+        |value, key| match value {
+            Value::Object(object) => object.get_mut(key),
+            Value::Array(array) => array.get_mut(key.parse::<usize>().ok()?),
+            _ => None,
+        },
+    )?;
+
+    // Array elements are replaced, so the other indices stay the same.
+    match parent {
+        Value::Object(object) => object.remove(key),
+        Value::Array(array) => array
+            .get_mut(key.parse::<usize>().ok()?)
+            .map(|element| std::mem::replace(element, Value::Null)),
+        _ => None,
+    }
+}
+
+/// Zeroes every occurrence of the given bytes in the data.
+pub fn zero_bytes(data: &mut [u8], bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    let mut offset = 0;
+    while offset + bytes.len() <= data.len() {
+        if data[offset..].starts_with(bytes) {
+            data[offset..offset + bytes.len()].fill(0);
+            offset += bytes.len();
+        } else {
+            offset += 1;
+        }
+    }
+}
+
+/// Checks if a decoded field matches the searched value.
+///
+/// Strings also match other values with the same text, as decoded numbers can be ambiguous.
+fn field_matches(field: &Value, value: &Value) -> bool {
+    match (field, value) {
+        (field, value) if field == value => true,
+        (Value::String(_), _) => false,
+        (field, Value::String(value)) => field.to_string() == *value,
+        _ => false,
+    }
+}
+
+/// Finds the buffered packets whose raw data contains the given byte pattern.
+///
+/// The pattern is written in hex, and `??` matches any byte. (e.g. `0a ?? 12`)\
+/// Returns the indices of the matching packets.
+#[tauri::command]
+pub fn sniffer__find_bytes(pattern_hex: String) -> MaybeError<Vec<u32>> {
+    let Some(pattern) = parse_pattern(pattern_hex) else {
+        return Err("capability.sniffer.error.bad-pattern");
+    };
+
+    Ok(sniffer::with_buffer(|packets| {
+        packets
+            .iter()
+            .filter(|packet| contains_pattern(&packet.binary, &pattern))
+            .map(|packet| packet.index)
+            .collect()
+    }))
+}
+
+/// Finds the buffered packets with a decoded field of the given value.
+///
+/// The field is referenced by a dot-separated path. (e.g. `1.2.0`)\
+/// Returns the indices of the matching packets.
+#[tauri::command]
+pub fn sniffer__find_field(json_path: String, value: Value) -> Vec<u32> {
+    sniffer::with_buffer(|packets| {
+        packets
+            .iter()
+            .filter(|packet| {
+                // Packets which failed to decode have no data.
+                let Ok(decoded) = serde_json::from_str::<Value>(&packet.data) else {
+                    return false;
+                };
+
+                resolve_field(&decoded, &json_path)
+                    .is_some_and(|field| field_matches(field, &value))
+            })
+            .map(|packet| packet.index)
+            .collect()
+    })
+}
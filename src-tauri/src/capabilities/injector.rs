@@ -0,0 +1,157 @@
+use crate::capabilities::sniffer::{self, VisualPacket, LOOPBACK_DEVICE};
+use crate::config::Config;
+use anyhow::{anyhow, Result};
+use std::net::UdpSocket;
+use std::time::{Duration, Instant};
+
+/// The magic number which starts a game packet.
+const PACKET_MAGIC_START: u16 = 0x4567;
+
+/// The magic number which ends a game packet.
+const PACKET_MAGIC_END: u16 = 0x89AB;
+
+/// The KCP conversation ID used for injected packets.
+const CONVERSATION: u32 = 0x00C0FFEE;
+
+/// The KCP token used for injected packets.
+const TOKEN: u32 = 0x1234ABCD;
+
+/// How long to wait for injected packets to be captured.
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Creates a handshake packet.
+///
+/// The client sends `0xFF`/`0xFFFFFFFF`; the server replies with `0x145`/`0x14514545`.
+fn handshake(magic_start: u32, magic_end: u32) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(20);
+    packet.extend_from_slice(&magic_start.to_be_bytes());
+    packet.extend_from_slice(&CONVERSATION.to_be_bytes());
+    packet.extend_from_slice(&TOKEN.to_be_bytes());
+    packet.extend_from_slice(&1234567890u32.to_be_bytes());
+    packet.extend_from_slice(&magic_end.to_be_bytes());
+    packet
+}
+
+/// Creates a game packet with the given ID and data.
+fn game_packet(id: u16, data: &[u8]) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(12 + data.len());
+    packet.extend_from_slice(&PACKET_MAGIC_START.to_be_bytes());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&0u16.to_be_bytes());
+    packet.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    packet.extend_from_slice(data);
+    packet.extend_from_slice(&PACKET_MAGIC_END.to_be_bytes());
+    packet
+}
+
+/// Wraps a payload in a KCP push segment.
+fn kcp_segment(sequence: u32, payload: &[u8]) -> Vec<u8> {
+    let mut segment = Vec::with_capacity(28 + payload.len());
+    segment.extend_from_slice(&CONVERSATION.to_le_bytes());
+    segment.extend_from_slice(&TOKEN.to_le_bytes());
+    segment.push(81); // IKCP_CMD_PUSH
+    segment.push(0); // fragment
+    segment.extend_from_slice(&256u16.to_le_bytes()); // window
+    segment.extend_from_slice(&0u32.to_le_bytes()); // timestamp
+    segment.extend_from_slice(&sequence.to_le_bytes());
+    segment.extend_from_slice(&0u32.to_le_bytes()); // unacknowledged
+    segment.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    segment.extend_from_slice(payload);
+    segment
+}
+
+/// Injects test packets on the loopback device.
+///
+/// This performs a handshake, then sends `count` packets from the client to the server port.\
+/// The packets aren't encrypted, so they only exercise the capture path.
+pub fn inject_packets(server_port: u16, count: u32) -> Result<()> {
+    let server = UdpSocket::bind(("127.0.0.1", server_port))?;
+    let client = UdpSocket::bind("127.0.0.1:0")?;
+    client.connect(server.local_addr()?)?;
+
+    // Perform the handshake.
+    client.send(&handshake(0xFF, 0xFFFFFFFF))?;
+    server.send_to(&handshake(0x145, 0x14514545), client.local_addr()?)?;
+
+    // Send the test packets.
+    for sequence in 0..count {
+        let payload = game_packet(1, format!("test packet {}", sequence).as_bytes());
+        client.send(&kcp_segment(sequence, &payload))?;
+    }
+
+    Ok(())
+}
+
+/// Checks that injected packets flow through the sniffer.
+///
+/// The sniffer must be configured to use the `loopback` device.\
+/// This returns the packets received before the timeout.
+pub async fn verify_capture(count: u32) -> Result<Vec<VisualPacket>> {
+    let config = Config::sniffer();
+    if config.device_name != LOOPBACK_DEVICE {
+        return Err(anyhow!(
+            "the sniffer must use the '{}' device",
+            LOOPBACK_DEVICE
+        ));
+    }
+
+    let Some(server_port) = config.server_ports.first().copied() else {
+        return Err(anyhow!("no server ports are configured"));
+    };
+
+    // Start the sniffer.
+    let (mut rx, shutdown_hook) = sniffer::run_sniffer(None).await.map_err(|e| anyhow!(e))?;
+
+    // Inject the packets.
+    let start_time = Instant::now();
+    inject_packets(server_port, count)?;
+
+    // Collect the packets which were captured.
+    let mut packets = Vec::new();
+    while packets.len() < count as usize {
+        let Some(remaining) = CAPTURE_TIMEOUT.checked_sub(start_time.elapsed()) else {
+            break;
+        };
+
+        match tokio::time::timeout(remaining, rx.recv()).await {
+            Ok(Some(packet)) => packets.push(VisualPacket::into_game(&packet, start_time)),
+            _ => break,
+        }
+    }
+
+    let _ = shutdown_hook.send(());
+
+    Ok(packets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn game_packets_are_framed() {
+        let packet = game_packet(0x1234, b"data");
+
+        assert_eq!(packet.len(), 12 + 4);
+        assert_eq!(packet[..2], PACKET_MAGIC_START.to_be_bytes());
+        assert_eq!(packet[2..4], 0x1234u16.to_be_bytes());
+        assert_eq!(packet[6..10], 4u32.to_be_bytes());
+        assert_eq!(&packet[10..14], b"data");
+        assert_eq!(packet[14..], PACKET_MAGIC_END.to_be_bytes());
+    }
+
+    /// This needs packet capture to be available on the loopback device,
+    /// which usually requires elevated privileges.
+    #[tokio::test]
+    #[ignore = "requires loopback packet capture"]
+    async fn injected_packets_are_captured() {
+        // Use a separate configuration file, so the user's isn't changed.
+        let directory = tempfile::tempdir().unwrap();
+        let _ = config::set_config_path(directory.path().join("config.yml"));
+        Config::get().sniffer.device_name = LOOPBACK_DEVICE.to_string();
+
+        let packets = verify_capture(10).await.unwrap();
+        assert_eq!(packets.len(), 10);
+    }
+}
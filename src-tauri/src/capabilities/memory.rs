@@ -0,0 +1,129 @@
+use crate::config::Config;
+use crate::state::SelectedProfile;
+use crate::utils::{MaybeError, Recover};
+use crate::{system, utils};
+use tauri::State;
+
+/// The maximum number of bytes which can be read at once.
+const MAX_READ_LENGTH: usize = 1024 * 1024;
+
+/// Reads a region of the running game's memory.
+///
+/// The region starts `offset` bytes after the base address of the game's executable.\
+/// The bytes are returned Base64-encoded, and can be fewer than requested.
+///
+/// This requires the launcher to be elevated, and `game.allow-memory-reads` to be enabled.
+///
+/// # Errors
+///
+/// Errors are not localized and need to be looked up by the
+/// caller before displaying to the user.
+#[tauri::command]
+pub fn memory__read(
+    profile: State<SelectedProfile>,
+    offset: u64,
+    length: usize,
+) -> MaybeError<String> {
+    if !Config::game().allow_memory_reads {
+        return Err("capability.memory.error.disabled");
+    }
+    if !system::is_elevated() {
+        return Err("game.error.launch.not-elevated");
+    }
+    if length == 0 || length > MAX_READ_LENGTH {
+        return Err("capability.memory.error.bad-length");
+    }
+
+    // Find the game process.
+    let Some(ref profile) = *profile.0.lock().recover() else {
+        return Err("game.error.launch.no-profile");
+    };
+    let executable = utils::get_executable_name(&profile.version.path);
+    let Some(process_id) = system::find_process_id(executable) else {
+        return Err("game.error.process.not-running");
+    };
+
+    read_memory(process_id, offset, length).map(|bytes| utils::base64_encode(&bytes))
+}
+
+/// Internal method used to read the memory of the game process.
+///
+/// # On Linux/macOS
+///
+/// The game runs under Wine, so its memory can't be read yet.
+#[cfg(unix)]
+fn read_memory(_: u32, _: u64, _: usize) -> MaybeError<Vec<u8>> {
+    Err("game.error.launch.unsupported")
+}
+
+/// Internal method used to read the memory of the game process.
+///
+/// # On Windows
+///
+/// This finds the base address of the game's executable with `EnumProcessModules`,
+/// then reads the region with `ReadProcessMemory`.
+#[cfg(windows)]
+fn read_memory(process_id: u32, offset: u64, length: usize) -> MaybeError<Vec<u8>> {
+    use std::ffi::c_void;
+    use std::mem::size_of;
+    use windows::Win32::Foundation::{CloseHandle, HANDLE, HMODULE};
+    use windows::Win32::System::Diagnostics::Debug::ReadProcessMemory;
+    use windows::Win32::System::ProcessStatus::EnumProcessModules;
+    use windows::Win32::System::Threading::{
+        OpenProcess, PROCESS_QUERY_INFORMATION, PROCESS_VM_READ,
+    };
+
+    /// Reads the region from the opened process.
+    unsafe fn read(process: HANDLE, offset: u64, length: usize) -> MaybeError<Vec<u8>> {
+        // The first module is the game's executable.
+        let mut module = HMODULE::default();
+        let mut needed = 0;
+        if let Err(error) = EnumProcessModules(
+            process,
+            &mut module,
+            size_of::<HMODULE>() as u32,
+            &mut needed,
+        ) {
+            warn!("Failed to find the game's base address: {}", error);
+            return Err("capability.memory.error.read-failed");
+        }
+
+        let Some(address) = (module.0 as u64).checked_add(offset) else {
+            return Err("capability.memory.error.bad-length");
+        };
+
+        let mut buffer = vec![0u8; length];
+        let mut read = 0;
+        if let Err(error) = ReadProcessMemory(
+            process,
+            address as *const c_void,
+            buffer.as_mut_ptr() as *mut c_void,
+            length,
+            Some(&mut read),
+        ) {
+            warn!("Failed to read the game's memory: {}", error);
+            return Err("capability.memory.error.read-failed");
+        }
+
+        buffer.truncate(read);
+        Ok(buffer)
+    }
+
+    // Open the game process.
+    let access = PROCESS_QUERY_INFORMATION | PROCESS_VM_READ;
+    let process = match unsafe { OpenProcess(access, false, process_id) } {
+        Ok(handle) => handle,
+        Err(error) => {
+            warn!("Failed to open the game process: {}", error);
+            return Err("game.error.process.open-failed");
+        }
+    };
+
+    let result = unsafe { read(process, offset, length) };
+
+    unsafe {
+        _ = CloseHandle(process);
+    }
+
+    result
+}
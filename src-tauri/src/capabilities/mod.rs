@@ -1 +1,6 @@
+pub mod analysis;
+#[cfg(feature = "packet-injector")]
+pub mod injector;
+pub mod memory;
+pub mod seeds;
 pub mod sniffer;
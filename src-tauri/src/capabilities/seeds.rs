@@ -0,0 +1,382 @@
+use crate::app;
+use crate::capabilities::sniffer;
+use crate::config::Config;
+use crate::system;
+use crate::utils::MaybeError;
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// The default number of seconds the seed diagnostic captures for.
+const DIAGNOSTIC_SECONDS: u64 = 10;
+
+/// Parses a line of the known seeds file.
+///
+/// Seeds are unsigned 64-bit integers written in decimal, one per line.\
+/// Surrounding whitespace is ignored.
+pub fn parse_seed<S: AsRef<str>>(line: S) -> Option<u64> {
+    line.as_ref().trim().parse().ok()
+}
+
+/// Formats a seed as a line of the known seeds file.
+pub fn format_seed(seed: u64) -> String {
+    seed.to_string()
+}
+
+/// The result of validating the known seeds file.
+///
+/// All line numbers start from 1.
+#[derive(Default, Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedsReport {
+    /// The number of valid, unique seeds.
+    pub valid: usize,
+
+    /// The line numbers of seeds which appeared earlier in the file.
+    pub duplicates: Vec<usize>,
+
+    /// The line numbers of lines which aren't valid seeds.
+    pub malformed: Vec<usize>,
+
+    /// The line numbers of blank lines.
+    pub blank: Vec<usize>,
+}
+
+impl SeedsReport {
+    /// Checks if the file has no problems.
+    pub fn is_clean(&self) -> bool {
+        self.duplicates.is_empty() && self.malformed.is_empty() && self.blank.is_empty()
+    }
+}
+
+/// The likely cause of a capture's packets not being readable.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Verdict {
+    /// Packets were captured, decrypted, and decoded.
+    Ok,
+
+    /// No traffic matched the filter, so the device, filter, or ports are likely wrong.
+    NoTraffic,
+
+    /// Traffic was captured, but none of it could be decrypted.
+    ///
+    /// The session's seed is unknown, likely because the login was not captured.
+    NoSeed,
+
+    /// Packets were decrypted, but none of them could be decoded.
+    Undecodable,
+}
+
+impl Verdict {
+    /// Determines the verdict from the counts of a diagnostic capture.
+    pub fn from_counts(frames: u64, decrypted: usize, decoded: usize) -> Self {
+        if frames == 0 && decrypted == 0 {
+            Verdict::NoTraffic
+        } else if decrypted == 0 {
+            Verdict::NoSeed
+        } else if decoded == 0 {
+            Verdict::Undecodable
+        } else {
+            Verdict::Ok
+        }
+    }
+
+    /// Describes the likely cause for the console.
+    pub fn describe(self) -> &'static str {
+        match self {
+            Verdict::Ok => "Packets are being decrypted and decoded.",
+            Verdict::NoTraffic => {
+                "No traffic was captured. Check the device, filter, and server ports."
+            }
+            Verdict::NoSeed => {
+                "Traffic was captured, but none of it was decrypted. The session's seed is unknown; capture the login."
+            }
+            Verdict::Undecodable => {
+                "Packets were decrypted, but none of them were decoded. The protocol may have changed."
+            }
+        }
+    }
+}
+
+/// The result of a diagnostic capture.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeedDiagnostics {
+    /// The number of frames which matched the capture filter.
+    pub frames: u64,
+
+    /// The number of packets which were decrypted.
+    ///
+    /// If this is above zero, a known or captured seed matched.
+    pub decrypted: usize,
+
+    /// The number of decrypted packets which could be decoded.
+    pub decoded: usize,
+
+    /// The number of valid seeds in the known seeds file.
+    pub known_seeds: usize,
+
+    /// The likely cause of packets not being readable.
+    pub verdict: Verdict,
+}
+
+impl SeedDiagnostics {
+    /// Creates the diagnostics from the counts of a capture.
+    pub fn from_counts(frames: u64, decrypted: usize, decoded: usize, known_seeds: usize) -> Self {
+        SeedDiagnostics {
+            frames,
+            decrypted,
+            decoded,
+            known_seeds,
+            verdict: Verdict::from_counts(frames, decrypted, decoded),
+        }
+    }
+}
+
+/// Counts the raw frames captured on a device, on a thread of its own.
+///
+/// This is used to tell an unknown seed apart from a capture which sees no traffic.
+pub struct FrameCounter {
+    /// The number of frames which matched the filter so far.
+    frames: Arc<AtomicU64>,
+
+    /// Set once the counter should stop capturing.
+    stop: Arc<AtomicBool>,
+
+    /// The thread counting the frames.
+    task: JoinHandle<Result<(), pcap::Error>>,
+}
+
+impl FrameCounter {
+    /// Starts counting the frames on the device which match the filter.
+    pub fn start(device: String, filter: String) -> Self {
+        let frames = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let task = {
+            let (frames, stop) = (frames.clone(), stop.clone());
+            tokio::task::spawn_blocking(move || count_frames(&device, &filter, &frames, &stop))
+        };
+
+        FrameCounter { frames, stop, task }
+    }
+
+    /// Returns the number of frames counted so far.
+    pub fn frames(&self) -> u64 {
+        self.frames.load(Ordering::Relaxed)
+    }
+
+    /// Stops counting, and returns the number of frames counted.
+    pub async fn stop(self) -> Result<u64> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.task.await??;
+
+        Ok(self.frames.load(Ordering::Relaxed))
+    }
+}
+
+/// Resolves the path to the known seeds file.
+pub fn seeds_file() -> Result<PathBuf> {
+    system::resolve_path(Config::sniffer().seeds_file)
+}
+
+/// Validates the contents of a known seeds file.
+///
+/// This returns the report and the valid, unique seeds in their original order.
+fn validate(contents: &str) -> (SeedsReport, Vec<u64>) {
+    let mut report = SeedsReport::default();
+    let mut seen = HashSet::new();
+    let mut seeds = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+
+        if line.trim().is_empty() {
+            report.blank.push(line_number);
+            continue;
+        }
+
+        let Some(seed) = parse_seed(line) else {
+            report.malformed.push(line_number);
+            continue;
+        };
+
+        if !seen.insert(seed) {
+            report.duplicates.push(line_number);
+            continue;
+        }
+
+        seeds.push(seed);
+    }
+
+    report.valid = seeds.len();
+
+    (report, seeds)
+}
+
+/// Reads the known seeds file.
+///
+/// If the file doesn't exist, it is treated as empty.
+fn read_seeds_file() -> Result<String> {
+    let path = seeds_file()?;
+    if !path.exists() {
+        return Ok(String::new());
+    }
+
+    Ok(fs::read_to_string(path)?)
+}
+
+/// Counts the valid, unique seeds in the known seeds file.
+pub fn known_seed_count() -> Result<usize> {
+    Ok(validate(&read_seeds_file()?).0.valid)
+}
+
+/// Validates the known seeds file.
+///
+/// This reports duplicate, malformed, and blank lines without changing the file.
+#[tauri::command]
+pub fn seeds__validate() -> MaybeError<SeedsReport> {
+    let Ok(contents) = read_seeds_file() else {
+        return Err("capability.sniffer.seeds.read-failed");
+    };
+
+    Ok(validate(&contents).0)
+}
+
+/// Repairs the known seeds file.
+///
+/// This removes duplicate, malformed, and blank lines.\
+/// The original file is backed up with a `.bak` extension first.
+///
+/// Returns the report of the problems which were removed.
+#[tauri::command]
+pub fn seeds__repair() -> MaybeError<SeedsReport> {
+    let Ok(path) = seeds_file() else {
+        return Err("capability.sniffer.seeds.read-failed");
+    };
+    let Ok(contents) = read_seeds_file() else {
+        return Err("capability.sniffer.seeds.read-failed");
+    };
+
+    // If there is nothing to repair, leave the file alone.
+    let (report, seeds) = validate(&contents);
+    if report.is_clean() {
+        return Ok(report);
+    }
+
+    // Back up the original file.
+    let mut backup = path.clone().into_os_string();
+    backup.push(".bak");
+
+    if let Err(error) = fs::write(backup, &contents) {
+        warn!("Failed to back up the known seeds file: {}", error);
+        return Err("capability.sniffer.seeds.write-failed");
+    }
+
+    // Write the cleaned seeds.
+    let mut cleaned = seeds
+        .into_iter()
+        .map(format_seed)
+        .collect::<Vec<String>>()
+        .join("\n");
+    cleaned.push('\n');
+
+    if let Err(error) = fs::write(&path, cleaned) {
+        warn!("Failed to write the known seeds file: {}", error);
+        return Err("capability.sniffer.seeds.write-failed");
+    }
+
+    Ok(report)
+}
+
+/// Captures for a short time to check if packets can be decrypted.
+///
+/// The frames matching the filter are counted separately from the decrypted packets.\
+/// This tells an unknown seed apart from a wrong device, filter, or ports.
+///
+/// By default, this captures for 10 seconds.
+#[tauri::command]
+pub async fn seeds__diagnose(seconds: Option<u64>) -> MaybeError<SeedDiagnostics> {
+    sniffer::check_capture()?;
+
+    // Hold the running flag, so the sniffer can't start during the diagnostic.
+    let Some(_running) = app::sniffer::claim_running() else {
+        return Err("capability.sniffer.error.running");
+    };
+
+    let Ok(known_seeds) = known_seed_count() else {
+        return Err("capability.sniffer.seeds.read-failed");
+    };
+
+    let duration = Duration::from_secs(seconds.unwrap_or(DIAGNOSTIC_SECONDS).clamp(1, 60));
+
+    // Start the sniffer before counting frames, so the device is resolved first.
+    let (mut rx, shutdown_hook) = match sniffer::run_sniffer(None).await {
+        Ok(result) => result,
+        Err(_) => return Err("capability.sniffer.error"),
+    };
+
+    // Count the raw frames on another thread.
+    let counter = FrameCounter::start(sniffer::device_name(), Config::sniffer().filter);
+
+    // Count the packets which were decrypted and decoded.
+    let deadline = tokio::time::Instant::now() + duration;
+    let (mut decrypted, mut decoded) = (0, 0);
+    while let Ok(Some(packet)) = tokio::time::timeout_at(deadline, rx.recv()).await {
+        decrypted += 1;
+        if protoshark::decode(&packet.data).is_ok() {
+            decoded += 1;
+        }
+    }
+
+    if let Err(error) = shutdown_hook.send(()) {
+        warn!("Failed to send shutdown signal: {}", error);
+    }
+
+    let frames = match counter.stop().await {
+        Ok(frames) => frames,
+        Err(error) => {
+            warn!("Failed to count captured frames: {}", error);
+            return Err("capability.sniffer.error");
+        }
+    };
+
+    Ok(SeedDiagnostics::from_counts(
+        frames,
+        decrypted,
+        decoded,
+        known_seeds,
+    ))
+}
+
+/// Counts the frames captured on the device which match the filter.
+///
+/// This captures until `stop` is set.
+fn count_frames(
+    device: &str,
+    filter: &str,
+    frames: &AtomicU64,
+    stop: &AtomicBool,
+) -> Result<(), pcap::Error> {
+    let mut capture = pcap::Capture::from_device(device)?.timeout(500).open()?;
+    capture.filter(filter, true)?;
+
+    while !stop.load(Ordering::Relaxed) {
+        match capture.next_packet() {
+            Ok(_) => {
+                frames.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(pcap::Error::TimeoutExpired) => continue,
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(())
+}
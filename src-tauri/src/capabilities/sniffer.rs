@@ -1,24 +1,69 @@
-use crate::config::{save_config, Config};
-use crate::utils::serde_base64;
-use crate::{system, utils};
+use crate::app::game::GameManager;
+use crate::capabilities::analysis;
+use crate::capabilities::seeds::{self, FrameCounter, SeedDiagnostics};
+use crate::config::{save_config, Config, Redaction};
+use crate::events::Event;
+use crate::utils::{serde_base64, MaybeError, Recover, Translate};
+use crate::{events, system, utils, GLOBAL_STATE};
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::{BasicHistory, Input, Select};
+use lazy_static::lazy_static;
 use log::{error, info, warn};
 use pcap::Device;
 use pcap_file::pcap::PcapReader;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt::{Display, Formatter};
-use std::fs::File;
-use std::io::Read;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, MutexGuard};
-use std::time::Instant;
-use tokio::sync::mpsc::UnboundedReceiver;
-use tokio::sync::Mutex;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{Receiver, Sender, UnboundedReceiver};
+use tokio::sync::{Mutex, Notify};
 use ys_sniffer::{Config as SnifferConfig, GamePacket, PacketSource};
 
+lazy_static! {
+    static ref DUMP_NAME_REGEX: Regex = Regex::new(r"^dump-([0-9]+)\.json(\.gz)?$").unwrap();
+    static ref FILTER_PORT_REGEX: Regex =
+        Regex::new(r"\bport(?:range)?\s+([0-9]+)(?:-([0-9]+))?").unwrap();
+}
+
+/// The packets shown in the packet visualizer.
+///
+/// This mirrors the visualizer's packet list, so packets can be referenced by their index.\
+/// Only the newest `sniffer.buffer-capacity` packets are kept.
+static PACKET_BUFFER: LazyLock<std::sync::Mutex<PacketBuffer>> =
+    LazyLock::new(|| std::sync::Mutex::new(PacketBuffer::default()));
+
+/// The user's notes on the buffered packets.
+///
+/// Notes are keyed by the index of the packet in the packet buffer.
+static PACKET_NOTES: LazyLock<std::sync::Mutex<BTreeMap<u32, String>>> =
+    LazyLock::new(|| std::sync::Mutex::new(BTreeMap::new()));
+
+/// The user's markers on the capture timeline.
+static CAPTURE_MARKERS: LazyLock<std::sync::Mutex<Vec<Marker>>> =
+    LazyLock::new(|| std::sync::Mutex::new(Vec::new()));
+
+/// When the current live capture started, if one is running.
+///
+/// Markers are placed relative to this time.
+static CAPTURE_START: LazyLock<std::sync::Mutex<Option<Instant>>> =
+    LazyLock::new(|| std::sync::Mutex::new(None));
+
+/// The number of packets dropped since the sniffer was started.
+///
+/// Packets are dropped when the packet queue is full.
+static DROPPED_PACKETS: AtomicU64 = AtomicU64::new(0);
+
+/// The device name which selects the system's loopback device.
+pub const LOOPBACK_DEVICE: &str = "loopback";
+
 /// A struct wrapper that allows the device to be displayed.
 struct CaptureDevice(Device);
 
@@ -46,6 +91,9 @@ impl Display for CaptureDevice {
 fn get_device(config: &mut MutexGuard<Config>) -> String {
     // Check if the device name exists in the config.
     let device_name = &config.sniffer.device_name;
+    if device_name == LOOPBACK_DEVICE {
+        return loopback_device().unwrap_or_else(|| device_name.clone());
+    }
     if !device_name.is_empty() {
         return device_name.clone();
     }
@@ -80,6 +128,72 @@ fn get_device(config: &mut MutexGuard<Config>) -> String {
     device.name.clone()
 }
 
+/// Returns the name of the network device used for capturing.
+///
+/// If no device is configured, the user is prompted to select one.
+pub fn device_name() -> String {
+    get_device(&mut Config::get())
+}
+
+/// Finds the name of the system's loopback device.
+fn loopback_device() -> Option<String> {
+    let Ok(device_list) = Device::list() else {
+        warn!("Failed to fetch device list.");
+        return None;
+    };
+
+    device_list
+        .into_iter()
+        .find(|device| device.flags.is_loopback())
+        .map(|device| device.name)
+}
+
+/// Checks if a network device with the given name exists.
+///
+/// The `loopback` name exists if the system has a loopback device.
+pub fn device_exists<S: AsRef<str>>(name: S) -> bool {
+    if name.as_ref() == LOOPBACK_DEVICE {
+        return loopback_device().is_some();
+    }
+
+    let Ok(device_list) = Device::list() else {
+        warn!("Failed to fetch device list.");
+        return false;
+    };
+
+    device_list.iter().any(|d| d.name == name.as_ref())
+}
+
+/// Checks if packet capture is available on this system.
+///
+/// This fails if npcap (on Windows) or libpcap isn't installed,\
+/// or if the launcher isn't permitted to capture packets.
+///
+/// # Errors
+///
+/// The error is a translation key, whose message explains how to fix the problem.
+pub fn check_capture() -> MaybeError<()> {
+    match Device::list() {
+        Ok(_) => Ok(()),
+        Err(pcap::Error::PcapError(message)) if message.to_lowercase().contains("permission") => {
+            warn!("Packet capture is not permitted: {}", message);
+            Err("capability.sniffer.error.pcap-permission")
+        }
+        Err(error) => {
+            warn!("Packet capture is unavailable: {}", error);
+            Err("capability.sniffer.error.pcap-missing")
+        }
+    }
+}
+
+/// Checks if the packet sniffer can be used on this system.
+///
+/// The frontend uses this to mark the sniffer as unavailable.
+#[tauri::command]
+pub fn sniffer__check() -> MaybeError<()> {
+    check_capture()
+}
+
 /// Holds more data about a `GamePacket`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct Packet {
@@ -100,19 +214,56 @@ struct Packet {
     ///
     /// This number is in milliseconds, and is an unsigned long.
     received: u128,
+
+    /// The UNIX timestamp of when the packet was received.
+    ///
+    /// This number is in milliseconds.\
+    /// Dumps created before this field existed will default to `0`.
+    #[serde(default)]
+    timestamp: u64,
+
+    /// The length of the packet's data.
+    ///
+    /// This is only set when the data wasn't stored. (metadata-only mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    length: Option<u64>,
 }
 
 impl Packet {
     /// Creates a new packet from the given data.
-    pub fn new(data: GamePacket, received: u128) -> Self {
+    pub fn new(data: GamePacket, received: u128, timestamp: u64) -> Self {
         Self {
             id: data.id,
             header: data.header,
             data: data.data,
             source: data.source,
             received,
+            timestamp,
+            length: None,
         }
     }
+
+    /// Creates a new packet from the given data, without storing its payload.
+    ///
+    /// Only the packet's ID, source, timing, and length are kept.
+    pub fn metadata(data: GamePacket, received: u128, timestamp: u64) -> Self {
+        Self {
+            id: data.id,
+            header: Vec::new(),
+            data: Vec::new(),
+            source: data.source,
+            received,
+            timestamp,
+            length: Some(data.data.len() as u64),
+        }
+    }
+
+    /// Returns the length of the packet's data.
+    ///
+    /// This is correct even if the data wasn't stored.
+    pub fn length(&self) -> u64 {
+        self.length.unwrap_or(self.data.len() as u64)
+    }
 }
 
 impl Display for Packet {
@@ -124,14 +275,26 @@ impl Display for Packet {
             self.source,
             utils::opposite(self.source),
             self.id,
-            self.data.len()
+            self.length()
         )
     }
 }
 
 /// Runs the sniffer for the CLI application.
-pub async fn run_cli() {
-    let (mut rx, shutdown_hook) = match run_sniffer().await {
+///
+/// If a filter is given, it is used instead of the configured filter for this run.\
+/// If a maximum number of packets is given, the capture stops once that many are stored.
+///
+/// This must be called from the runtime created in `main`, which the packet reader is spawned on.
+pub async fn run_cli(filter: Option<String>, max_packets: Option<usize>) {
+    // Check if packets can be captured.
+    if let Err(error) = check_capture().translated() {
+        error!("{}", error);
+        std::process::exit(1);
+    }
+
+    let frame_filter = filter.clone().unwrap_or_else(|| Config::sniffer().filter);
+    let (rx, shutdown_hook) = match run_sniffer(filter).await {
         Ok((shutdown_hook, rx)) => (shutdown_hook, rx),
         Err(error) => {
             error!("Failed to run the sniffer: {:#?}", error);
@@ -139,61 +302,57 @@ pub async fn run_cli() {
         }
     };
 
+    // Count the raw frames, so the stats can tell an unknown seed apart from no traffic.
+    let frame_counter = FrameCounter::start(device_name(), frame_filter);
+
     // Create mutex for storing packets.
-    let mut start_time: Option<Instant> = None;
+    let metadata_only = Config::sniffer().metadata_only;
 
     let log_enabled = Arc::new(AtomicBool::new(false));
     let packets = Arc::new(Mutex::new(Vec::new()));
-
-    // Spawn a thread to read the packets.
-    let do_log = log_enabled.clone();
-    let packet_list = packets.clone();
-
-    tokio::spawn(async move {
-        while let Some(packet) = rx.recv().await {
-            if start_time.is_none() {
-                start_time = Some(Instant::now());
-            }
-
-            // Lock the list to push the packet.
-            let mut list = packet_list.lock().await;
-
-            // Create a new packet with the current time.
-            let current_time = Instant::now();
-            let packet = Packet::new(
-                packet,
-                current_time.duration_since(start_time.unwrap()).as_millis(),
-            );
-
-            // Write the packet to the console.
-            if do_log.load(Ordering::Relaxed) {
-                info!("{}", packet);
+    let limit_reached = Arc::new(Notify::new());
+
+    // Spawn a task to read the packets.
+    let reader = tokio::spawn(read_packets(
+        rx,
+        PacketReader {
+            packets: packets.clone(),
+            log_enabled: log_enabled.clone(),
+            limit_reached: limit_reached.clone(),
+            max_packets,
+            metadata_only,
+        },
+    ));
+
+    // Read user input on its own thread, so the capture can stop without it.
+    let (command_tx, mut commands) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        let mut history = BasicHistory::new().max_entries(8).no_duplicates(true);
+
+        loop {
+            // Read the console for user commands.
+            let command = match Input::<String>::with_theme(&ColorfulTheme::default())
+                .history_with(&mut history)
+                .interact_text()
+            {
+                Ok(command) => command,
+                Err(error) => {
+                    error!("Failed to read command: {:#?}", error);
+                    continue;
+                }
+            };
+
+            if command_tx.send(command).is_err() {
+                break;
             }
-
-            // Push the packet to the list.
-            list.push(packet);
-
-            // Drop the list to be used again later.
-            drop(list);
         }
-
-        info!("Sniffer has finished sniffing.");
     });
 
-    // Prepare for user input.
-    let mut history = BasicHistory::new().max_entries(8).no_duplicates(true);
-
     loop {
-        // Read the console for user commands.
-        let command = match Input::<String>::with_theme(&ColorfulTheme::default())
-            .history_with(&mut history)
-            .interact_text()
-        {
-            Ok(command) => command,
-            Err(error) => {
-                error!("Failed to read command: {:#?}", error);
-                continue;
-            }
+        // Wait for a user command, or for the capture to finish.
+        let command = tokio::select! {
+            Some(command) = commands.recv() => command,
+            _ = limit_reached.notified() => break
         };
 
         // Parse the command and execute it.
@@ -209,35 +368,328 @@ pub async fn run_cli() {
                     if !enabled { "enabled" } else { "disabled" }
                 );
             }
+            "stats" => {
+                let list = packets.lock().await;
+
+                // Metadata-only packets have no data to decode, so they count as decoded.
+                let decoded = if metadata_only {
+                    list.len()
+                } else {
+                    list.iter()
+                        .filter(|packet| protoshark::decode(&packet.data).is_ok())
+                        .count()
+                };
+
+                let known_seeds = seeds::known_seed_count().unwrap_or_else(|error| {
+                    warn!("Failed to read the known seeds file: {}", error);
+                    0
+                });
+                let diagnostics = SeedDiagnostics::from_counts(
+                    frame_counter.frames(),
+                    list.len(),
+                    decoded,
+                    known_seeds,
+                );
+
+                info!("Frames captured:   {}", diagnostics.frames);
+                info!("Packets decrypted: {}", diagnostics.decrypted);
+                if !metadata_only {
+                    info!("Packets decoded:   {}", diagnostics.decoded);
+                }
+                info!("Packets dropped:   {}", dropped_packets());
+                info!("Known seeds:       {}", diagnostics.known_seeds);
+                info!("{}", diagnostics.verdict.describe());
+            }
             "help" => {
                 info!("Commands:");
-                info!("  stop - Stops the sniffer.");
-                info!("  log  - Toggles logging of packets.");
-                info!("  help - Shows this help message.");
+                info!("  stop  - Stops the sniffer.");
+                info!("  log   - Toggles logging of packets.");
+                info!("  stats - Shows how many frames were captured, and how many packets were decrypted and decoded.");
+                info!("  help  - Shows this help message.");
             }
             _ => info!("Unknown command: '{command}'"),
         }
     }
 
+    // If we hit here, we should stop the sniffer.
+    if let Err(error) = shutdown_hook.send(()) {
+        warn!("Failed to send shutdown signal: {}", error);
+    }
+
+    if let Err(error) = frame_counter.stop().await {
+        warn!("Failed to count captured frames: {}", error);
+    }
+
+    // Wait for the reader to finish with the remaining packets, so they are dumped too.
+    match tokio::time::timeout(Duration::from_secs(5), reader).await {
+        Ok(Err(error)) => warn!("The packet reader failed: {}", error),
+        Err(_) => warn!("The packet reader didn't finish in time."),
+        Ok(Ok(())) => {}
+    }
+
     // Dump the packets to the file system.
-    let encoded = serde_json::to_string_pretty(&*packets.lock().await).unwrap();
+    let packets = packets.lock().await;
+    let dump = Dump {
+        version: DUMP_VERSION,
+        header: DumpHeader::from_selected().await,
+        packets: &packets,
+        notes: BTreeMap::new(),
+        markers: Vec::new(),
+    };
 
-    let Ok(app_data_dir) = utils::app_data_dir() else {
-        error!("Failed to fetch the application data directory.");
-        std::process::exit(1);
+    match write_dump(&dump) {
+        Ok(path) => info!("Packets have been dumped to {}.", path.display()),
+        Err(error) => error!("Failed to write the packet dump: {:#?}", error),
+    }
+
+    info!("Sniffer has been shut down.");
+}
+
+/// The state shared between the CLI and its packet reader.
+struct PacketReader {
+    /// The packets which were read.
+    packets: Arc<Mutex<Vec<Packet>>>,
+
+    /// Whether read packets are written to the console.
+    log_enabled: Arc<AtomicBool>,
+
+    /// Notified once `max_packets` packets were read.
+    limit_reached: Arc<Notify>,
+
+    /// The number of packets to read before stopping, if any.
+    max_packets: Option<usize>,
+
+    /// Whether only the packets' metadata is kept.
+    metadata_only: bool,
+}
+
+/// Reads captured packets into the reader's packet list.
+///
+/// This runs until the sniffer stops, or until the maximum number of packets is read.
+async fn read_packets(mut rx: Receiver<GamePacket>, reader: PacketReader) {
+    let PacketReader {
+        packets,
+        log_enabled,
+        limit_reached,
+        max_packets,
+        metadata_only,
+    } = reader;
+    let mut start_time: Option<Instant> = None;
+
+    while let Some(packet) = rx.recv().await {
+        let start_time = *start_time.get_or_insert_with(Instant::now);
+
+        // Lock the list to push the packet.
+        let mut list = packets.lock().await;
+
+        // Create a new packet with the current time.
+        let received = Instant::now().duration_since(start_time).as_millis();
+        let timestamp = utils::unix_timestamp_millis();
+
+        let packet = if metadata_only {
+            Packet::metadata(packet, received, timestamp)
+        } else {
+            Packet::new(packet, received, timestamp)
+        };
+
+        // Write the packet to the console.
+        if log_enabled.load(Ordering::Relaxed) {
+            info!("{}", packet);
+        }
+
+        // Push the packet to the list.
+        list.push(packet);
+
+        // Stop storing packets once the limit is reached.
+        if max_packets.is_some_and(|max_packets| list.len() >= max_packets) {
+            info!("Captured {} packets; stopping the sniffer.", list.len());
+            limit_reached.notify_one();
+            break;
+        }
+    }
+
+    info!("Sniffer has finished sniffing.");
+}
+
+/// Writes a dump of captured packets to the dumps directory.
+///
+/// If the dump can't be serialized, the packets are written as plain text instead.\
+/// Returns the path of the written file.
+fn write_dump(dump: &Dump<Packet>) -> anyhow::Result<PathBuf> {
+    let dumps_dir = dumps_dir()?;
+
+    let encoded = match serde_json::to_string_pretty(dump) {
+        Ok(encoded) => encoded,
+        Err(error) => {
+            error!("Failed to serialize the packet dump: {:#?}", error);
+
+            // Fall back to writing each packet on its own line.
+            let lines = dump
+                .packets
+                .iter()
+                .map(|packet| packet.to_string())
+                .collect::<Vec<_>>();
+
+            let (path, mut file) = create_dump_file(&dumps_dir, "txt")?;
+            file.write_all(lines.join("\n").as_bytes())?;
+
+            return Ok(path);
+        }
     };
 
-    let path = app_data_dir
-        .join("dumps")
-        .join(format!("dump-{}.json", utils::unix_timestamp()));
-    if let Err(error) = utils::write_file(&path, encoded) {
-        error!("Failed to write the packet dump: {:#?}", error);
+    let compress = Config::sniffer().compress_dumps;
+    let extension = if compress { "json.gz" } else { "json" };
+
+    let (path, file) = create_dump_file(&dumps_dir, extension)?;
+    write_encoded(file, encoded, compress)?;
+
+    Ok(path)
+}
+
+/// Creates the file for a new dump, which didn't exist before.
+///
+/// Dumps are named after the current time in milliseconds.\
+/// If a dump with that name already exists, the timestamp is bumped until it is unused.\
+/// The file is created atomically, so concurrent dumps never share a file.
+fn create_dump_file(dumps_dir: &Path, extension: &str) -> std::io::Result<(PathBuf, File)> {
+    let mut timestamp = utils::unix_timestamp_millis();
+    loop {
+        let path = dumps_dir.join(format!("dump-{}.{}", timestamp, extension));
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => timestamp += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Writes an encoded dump to the file, compressing it with gzip if requested.
+fn write_encoded(mut file: File, encoded: String, compress: bool) -> anyhow::Result<()> {
+    if compress {
+        utils::write_compressed(file, encoded)
+    } else {
+        Ok(file.write_all(encoded.as_bytes())?)
     }
+}
 
-    // If we hit here, we should stop the sniffer.
-    shutdown_hook.send(()).unwrap();
+/// Returns the path to the directory where packet dumps are stored.
+///
+/// This is read from the configuration, and is created if it doesn't exist.
+pub fn dumps_dir() -> anyhow::Result<PathBuf> {
+    let dumps_dir = system::resolve_path(Config::sniffer().dump_dir)?;
 
-    info!("Sniffer has been shut down.");
+    // Create the directory if it doesn't exist.
+    if !dumps_dir.exists() {
+        fs::create_dir_all(&dumps_dir)?;
+    }
+
+    Ok(dumps_dir)
+}
+
+/// Extracts the timestamp embedded in a dump's file name.
+///
+/// The timestamp is returned in milliseconds.\
+/// Dumps named before this used seconds, so their timestamps are converted.
+///
+/// Returns `None` if the file name is not in the `dump-<timestamp>.json(.gz)` format.
+fn dump_timestamp<S: AsRef<str>>(file_name: S) -> Option<u64> {
+    let timestamp: u64 = DUMP_NAME_REGEX
+        .captures(file_name.as_ref())
+        .and_then(|c| c.get(1))
+        .and_then(|m| m.as_str().parse().ok())?;
+
+    // Timestamps in seconds won't reach this until the year 5138.
+    if timestamp < 100_000_000_000 {
+        Some(timestamp * 1000)
+    } else {
+        Some(timestamp)
+    }
+}
+
+/// Lists all packet dumps in the dumps directory.
+///
+/// The dumps are sorted by the timestamp in their file name, newest first.\
+/// Files which aren't named like dumps are skipped.
+fn list_dumps() -> anyhow::Result<Vec<(u64, PathBuf)>> {
+    let dumps_dir = dumps_dir()?;
+    let mut dumps = fs::read_dir(&dumps_dir)?
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name();
+            let timestamp = dump_timestamp(file_name.to_string_lossy())?;
+
+            Some((timestamp, entry.path()))
+        })
+        .collect::<Vec<_>>();
+
+    dumps.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    Ok(dumps)
+}
+
+/// Returns the path to the most recent packet dump.
+///
+/// Dumps are ordered by the timestamp in their file name, not their modification time.\
+/// If no dumps exist, this returns `None`.
+#[tauri::command]
+pub fn sniffer__latest_dump() -> MaybeError<Option<String>> {
+    let Ok(dumps) = list_dumps() else {
+        return Err("capability.sniffer.dumps.read-failed");
+    };
+
+    Ok(dumps
+        .first()
+        .map(|(_, path)| path.to_string_lossy().to_string()))
+}
+
+/// Deletes old packet dumps.
+///
+/// Dumps older than `max_age_days` are deleted, then only the newest `max_count` dumps are kept.\
+/// Files which aren't dumps are never deleted.
+///
+/// Returns the paths of the deleted dumps.
+pub fn prune_dumps(
+    max_age_days: Option<u64>,
+    max_count: Option<usize>,
+) -> anyhow::Result<Vec<String>> {
+    let dumps = list_dumps()?;
+
+    // Determine the oldest timestamp to keep.
+    let cutoff =
+        max_age_days.map(|days| utils::unix_timestamp_millis().saturating_sub(days * 86_400_000));
+
+    let mut deleted = Vec::new();
+    for (index, (timestamp, path)) in dumps.iter().enumerate() {
+        let too_old = cutoff.is_some_and(|cutoff| *timestamp < cutoff);
+        let too_many = max_count.is_some_and(|max_count| index >= max_count);
+        if !too_old && !too_many {
+            continue;
+        }
+
+        // Delete the dump.
+        if let Err(error) = fs::remove_file(path) {
+            warn!("Failed to delete dump {}: {}", path.display(), error);
+            continue;
+        }
+
+        deleted.push(path.to_string_lossy().to_string());
+    }
+
+    Ok(deleted)
+}
+
+/// Deletes old packet dumps.
+///
+/// See `prune_dumps` for how dumps are chosen.
+#[tauri::command]
+pub fn dumps__prune(
+    max_age_days: Option<u64>,
+    max_count: Option<usize>,
+) -> MaybeError<Vec<String>> {
+    prune_dumps(max_age_days, max_count).map_err(|error| {
+        warn!("Failed to prune dumps: {}", error);
+        "capability.sniffer.dumps.prune-failed"
+    })
 }
 
 /// This is the result that `run_sniffer` returns.
@@ -246,39 +698,197 @@ pub async fn run_cli() {
 ///
 /// 1. The packet receiver.
 /// 2. The sniffer's shutdown hook.
-type SnifferRunResult = (UnboundedReceiver<GamePacket>, crossbeam_channel::Sender<()>);
+type SnifferRunResult = (Receiver<GamePacket>, crossbeam_channel::Sender<()>);
+
+/// Which packets to drop once the packet queue is full.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Overflow {
+    DropOldest,
+    DropNewest,
+}
+
+impl Overflow {
+    /// Reads the overflow policy from the sniffer configuration.
+    ///
+    /// Unknown values fall back to `DropOldest`.
+    fn from_config(config: &crate::config::Sniffer) -> Self {
+        match config.queue_overflow.to_lowercase().as_str() {
+            "drop-oldest" => Overflow::DropOldest,
+            "drop-newest" => Overflow::DropNewest,
+            other => {
+                warn!("Unknown queue overflow policy '{other}'; dropping the oldest packets.");
+                Overflow::DropOldest
+            }
+        }
+    }
+}
+
+/// Statistics about the running sniffer.
+#[derive(Serialize, Debug, Clone)]
+pub struct SnifferStats {
+    /// The number of packets dropped because the queue was full.
+    pub dropped: u64,
+
+    /// The number of packets in the packet buffer.
+    pub buffered: usize,
+}
+
+/// Returns statistics about the sniffer.
+#[tauri::command]
+pub fn sniffer__stats() -> SnifferStats {
+    SnifferStats {
+        dropped: dropped_packets(),
+        buffered: PACKET_BUFFER.lock().recover().packets.len(),
+    }
+}
+
+/// Returns the number of packets dropped since the sniffer was started.
+pub fn dropped_packets() -> u64 {
+    DROPPED_PACKETS.load(Ordering::Relaxed)
+}
+
+/// Forwards captured packets from the sniffer to a bounded channel.
+///
+/// Packets wait in a queue of up to `capacity` packets until the receiver is ready.\
+/// Once the queue is full, packets are dropped according to the overflow policy.
+async fn forward_packets(
+    mut rx: UnboundedReceiver<GamePacket>,
+    tx: Sender<GamePacket>,
+    capacity: usize,
+    overflow: Overflow,
+) {
+    let mut queue = VecDeque::new();
+
+    loop {
+        tokio::select! {
+            packet = rx.recv() => {
+                // The sniffer has stopped.
+                let Some(packet) = packet else {
+                    break;
+                };
+
+                if queue.len() < capacity {
+                    queue.push_back(packet);
+                    continue;
+                }
+
+                DROPPED_PACKETS.fetch_add(1, Ordering::Relaxed);
+                if overflow == Overflow::DropOldest {
+                    queue.pop_front();
+                    queue.push_back(packet);
+                }
+            }
+            permit = tx.reserve(), if !queue.is_empty() => {
+                // The receiver has stopped listening.
+                let Ok(permit) = permit else {
+                    return;
+                };
+
+                if let Some(packet) = queue.pop_front() {
+                    permit.send(packet);
+                }
+            }
+        }
+    }
+
+    // Send the packets which were still queued.
+    for packet in queue {
+        if tx.send(packet).await.is_err() {
+            break;
+        }
+    }
+
+    let dropped = dropped_packets();
+    if dropped > 0 {
+        warn!("{dropped} packets were dropped because the sniffer fell behind.");
+    }
+}
+
+/// Checks if the packet capturing filter is valid.
+pub fn filter_compiles<S: AsRef<str>>(filter: S) -> bool {
+    match pcap::Capture::dead(pcap::Linktype::ETHERNET) {
+        Ok(capture) => capture.compile(filter.as_ref(), true).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Derives the server ports from a packet capturing filter.
+///
+/// This reads `port <n>` and `portrange <a>-<b>` expressions.\
+/// Returns an empty list if the filter doesn't specify any ports.
+pub fn filter_ports<S: AsRef<str>>(filter: S) -> Vec<u16> {
+    FILTER_PORT_REGEX
+        .captures_iter(filter.as_ref())
+        .flat_map(|captures| {
+            let start = captures[1].parse::<u16>().unwrap_or_default();
+            let end = captures
+                .get(2)
+                .and_then(|end| end.as_str().parse::<u16>().ok())
+                .unwrap_or(start);
+
+            start..=end
+        })
+        .filter(|port| *port != 0)
+        .collect()
+}
 
 /// Runs the actual sniffer.
 ///
-/// Pulls the configuration for the sniffer from the global config.
-pub async fn run_sniffer() -> Result<SnifferRunResult, &'static str> {
-    let mut config = Config::get();
+/// Pulls the configuration for the sniffer from the global config.\
+/// If a filter is given, it overrides the configured filter and server ports.
+pub async fn run_sniffer(filter: Option<String>) -> Result<SnifferRunResult, &'static str> {
+    let mut config = Config::sniffer();
+
+    // Apply the filter override.
+    if let Some(filter) = filter {
+        if !filter_compiles(&filter) {
+            return Err("Invalid packet capturing filter.");
+        }
+
+        let ports = filter_ports(&filter);
+        if !ports.is_empty() {
+            config.server_ports = ports;
+        }
+
+        config.filter = filter;
+    }
 
     // Resolve the seeds file.
-    let seeds_file = match system::resolve_path(&config.sniffer.seeds_file) {
+    let seeds_file = match system::resolve_path(&config.seeds_file) {
         Ok(path) => path.to_string_lossy().to_string(),
         Err(_) => "known-seeds.txt".to_string(),
     };
 
+    // Read how packets are queued before the configuration is consumed.
+    let capacity = config.queue_capacity.max(1);
+    let overflow = Overflow::from_config(&config);
+
     // Prepare the sniffer configuration.
+    // The configuration lock is only held while resolving the device.
     let sniffer_config = SnifferConfig {
-        device_name: Some(get_device(&mut config)),
+        device_name: Some(get_device(&mut Config::get())),
         known_seeds: seeds_file,
-        filter: Some(config.sniffer.filter.clone()),
-        server_port: config.sniffer.server_ports.clone(),
+        filter: Some(config.filter),
+        server_port: config.server_ports,
     };
 
-    // Drop the lock so we don't carry it across await points.
-    drop(config);
-
     // Create the sending/receiving channel.
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<GamePacket>();
 
     // Run the sniffer.
-    let shutdown_hook = ys_sniffer::sniff_async(sniffer_config, tx)
-        .map_err(|_| "Failed to run the sniffer.")?;
+    let shutdown_hook = ys_sniffer::sniff_async(sniffer_config, tx).map_err(|_| {
+        events::emit_global(Event::SnifferError("capability.sniffer.error".to_string()));
+        "Failed to run the sniffer."
+    })?;
+
+    // Bound the packets which can wait to be processed.
+    // Packets wait in the forwarding queue, so the channel only hands them over.
+    let (queue_tx, queue_rx) = tokio::sync::mpsc::channel::<GamePacket>(1);
 
-    Ok((rx, shutdown_hook))
+    DROPPED_PACKETS.store(0, Ordering::Relaxed);
+    tokio::spawn(forward_packets(rx, queue_tx, capacity, overflow));
+
+    Ok((queue_rx, shutdown_hook))
 }
 
 /// A packet that is displayed on the frontend.
@@ -290,6 +900,12 @@ pub struct VisualPacket {
     /// This is offset from the connection was created.
     time: f32,
 
+    /// The UNIX timestamp of when the packet was received.
+    ///
+    /// This number is in milliseconds, and is `0` if unknown.
+    #[serde(default)]
+    timestamp: u64,
+
     /// The source of the packet.
     #[serde(with = "src_string")]
     source: PacketSource,
@@ -308,13 +924,13 @@ pub struct VisualPacket {
     length: u64,
 
     /// The packet's decoded data to be shown to the user.
-    data: String,
+    pub data: String,
 
     /// The raw binary packet data.
     ///
     /// This will be Base64-encoded
     #[serde(with = "serde_base64")]
-    binary: Vec<u8>,
+    pub binary: Vec<u8>,
 
     /// The index of the packet.
     ///
@@ -336,6 +952,7 @@ impl VisualPacket {
 
         Self {
             time: Instant::now().duration_since(start_time).as_secs_f32(),
+            timestamp: utils::unix_timestamp_millis(),
             source: packet.source,
             packet_id: packet.id,
             packet_name: packet.id.to_string(),
@@ -345,55 +962,662 @@ impl VisualPacket {
             index: 0
         }
     }
+
+    /// Redacts sensitive data from the packet.
+    ///
+    /// The raw data is only zeroed where the redacted values can be found.
+    pub fn redact(&mut self, redaction: &Redaction) {
+        // Redact the entire packet if specified.
+        if redaction.packet_ids.contains(&self.packet_id) {
+            self.data = String::new();
+            self.binary.fill(0);
+            return;
+        }
+
+        // Packets which failed to decode have no fields to redact.
+        let Ok(mut decoded) = serde_json::from_str::<Value>(&self.data) else {
+            return;
+        };
+
+        for path in &redaction.fields {
+            let Some(removed) = analysis::remove_field(&mut decoded, path) else {
+                continue;
+            };
+
+            // Zero the text in the raw data too.
+            if let Value::String(text) = removed {
+                analysis::zero_bytes(&mut self.binary, text.as_bytes());
+            }
+        }
+
+        self.data = decoded.to_string();
+    }
+
+    /// Removes the packet's payload, keeping only its metadata.
+    ///
+    /// This is used in metadata-only mode.
+    pub fn into_metadata(self) -> Self {
+        Self {
+            data: String::new(),
+            binary: Vec::new(),
+            ..self
+        }
+    }
 }
 
-/// Reads and parses the selected file for packets.
+/// The newest packets shown in the packet visualizer.
+#[derive(Default)]
+struct PacketBuffer {
+    /// The buffered packets, oldest first.
+    packets: VecDeque<VisualPacket>,
+
+    /// The index of the next buffered packet.
+    ///
+    /// This keeps counting when old packets are removed, so indices still match the visualizer's list.
+    next_index: u32,
+}
+
+/// Adds a packet to the packet buffer.
 ///
-/// If the file is in a JSON file, it will try to be parsed as a `Packet` or `VisualPacket`.
+/// The packet's index is set to its position in the visualizer's list.\
+/// Once the buffer holds `capacity` packets, the oldest packet and its note are removed.
+pub fn buffer_packet(mut packet: VisualPacket, capacity: usize) -> VisualPacket {
+    let mut buffer = PACKET_BUFFER.lock().recover();
+
+    packet.index = buffer.next_index;
+    buffer.next_index += 1;
+    buffer.packets.push_back(packet.clone());
+
+    while buffer.packets.len() > capacity.max(1) {
+        if let Some(removed) = buffer.packets.pop_front() {
+            PACKET_NOTES.lock().recover().remove(&removed.index);
+        }
+    }
+
+    packet
+}
+
+/// Returns a copy of the buffered packet at the given index.
 ///
-/// If the file is in `pcap` format, it will try to be parsed as a `Packet`.
+/// Returns `None` if the packet was removed from the buffer.
+pub fn buffered_packet(index: u32) -> Option<VisualPacket> {
+    let buffer = PACKET_BUFFER.lock().recover();
+    let first_index = buffer.packets.front()?.index;
+
+    let position = index.checked_sub(first_index)?;
+    buffer.packets.get(position as usize).cloned()
+}
+
+/// Runs the given function with the contents of the packet buffer.
+///
+/// The buffer is locked while the function runs.
+pub fn with_buffer<R, F: FnOnce(&[VisualPacket]) -> R>(f: F) -> R {
+    f(PACKET_BUFFER.lock().recover().packets.make_contiguous())
+}
+
+/// Removes the buffered packets and their notes, for a new capture.
+///
+/// Unlike clearing the buffer, indices keep counting, so they still match the visualizer's list.
+pub fn reset_buffer() {
+    PACKET_BUFFER.lock().recover().packets.clear();
+    PACKET_NOTES.lock().recover().clear();
+}
+
+/// Clears the packet buffer.
+///
+/// This should be called when the visualizer's packet list is cleared.\
+/// The user's notes and markers are cleared too.
 #[tauri::command]
-pub fn sniffer__load(file_path: String) -> Result<Vec<VisualPacket>, &'static str> {
-    // Read the file.
-    let file_path = PathBuf::from(file_path);
-    let Ok(file) = File::open(&file_path) else {
-        return Err("Failed to open the file.");
+pub fn sniffer__clear_buffer() {
+    *PACKET_BUFFER.lock().recover() = PacketBuffer::default();
+    PACKET_NOTES.lock().recover().clear();
+    CAPTURE_MARKERS.lock().recover().clear();
+}
+
+/// Copies the buffered packets and their notes, to be saved as a dump.
+///
+/// Dumps index notes by the position of their packet, so the notes are re-indexed.
+fn buffered_dump() -> (Vec<VisualPacket>, BTreeMap<u32, String>) {
+    let packets = with_buffer(|packets| packets.to_vec());
+    let first_index = packets.first().map_or(0, |packet| packet.index);
+
+    let notes = PACKET_NOTES
+        .lock()
+        .recover()
+        .iter()
+        .filter(|(index, _)| **index >= first_index)
+        .map(|(index, note)| (index - first_index, note.clone()))
+        .collect();
+
+    (packets, notes)
+}
+
+/// Sets the note on a buffered packet.
+///
+/// If the text is empty, the note is removed.
+#[tauri::command]
+pub fn sniffer__set_note(index: u32, text: String) -> MaybeError<()> {
+    if buffered_packet(index).is_none() {
+        return Err("capability.sniffer.error.bad-index");
+    }
+
+    let mut notes = PACKET_NOTES.lock().recover();
+    if text.trim().is_empty() {
+        notes.remove(&index);
+    } else {
+        notes.insert(index, text);
+    }
+
+    Ok(())
+}
+
+/// Returns the notes on the buffered packets.
+///
+/// Notes are keyed by the index of the packet.
+#[tauri::command]
+pub fn sniffer__get_notes() -> BTreeMap<u32, String> {
+    PACKET_NOTES.lock().recover().clone()
+}
+
+/// A named marker on the capture timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Marker {
+    /// The time of the marker.
+    ///
+    /// Like a `VisualPacket`'s time, this is in seconds since the capture started.
+    pub time: f32,
+
+    /// The label of the marker.
+    pub label: String,
+}
+
+/// Sets when the current live capture started.
+///
+/// Pass `None` when the capture stops.
+pub fn set_capture_start(start: Option<Instant>) {
+    *CAPTURE_START.lock().recover() = start;
+}
+
+/// Adds a marker at the current capture time.
+///
+/// If no live capture is running, the marker is placed at the last buffered packet.
+#[tauri::command]
+pub fn sniffer__add_marker(label: String) -> Marker {
+    let time = match *CAPTURE_START.lock().recover() {
+        Some(start) => start.elapsed().as_secs_f32(),
+        None => with_buffer(|packets| packets.last().map(|packet| packet.time).unwrap_or_default()),
     };
 
-    // Check if the data is a packet capture.
-    let data = match utils::read_file(&file_path) {
+    let marker = Marker { time, label };
+    CAPTURE_MARKERS.lock().recover().push(marker.clone());
+
+    marker
+}
+
+/// Returns the markers on the capture timeline.
+#[tauri::command]
+pub fn sniffer__get_markers() -> Vec<Marker> {
+    CAPTURE_MARKERS.lock().recover().clone()
+}
+
+/// Saves the buffered packets, their notes, and the markers as a dump.
+///
+/// If the path ends with `.gz`, the dump is compressed with gzip.
+#[tauri::command]
+pub fn sniffer__save_dump(file_path: String) -> MaybeError<()> {
+    let (packets, notes) = buffered_dump();
+    let dump = Dump {
+        version: DUMP_VERSION,
+        header: None,
+        packets: &packets,
+        notes,
+        markers: CAPTURE_MARKERS.lock().recover().clone(),
+    };
+
+    save_dump(&PathBuf::from(file_path), &dump).map_err(|error| {
+        warn!("Failed to save the packet dump: {}", error);
+        "capability.sniffer.dumps.write-failed"
+    })
+}
+
+/// Saves the buffered packets as a dump, with sensitive data redacted.
+///
+/// What is redacted is read from the configuration.\
+/// The buffered packets themselves are not changed.
+#[tauri::command]
+pub fn sniffer__export_redacted(file_path: String) -> MaybeError<()> {
+    let redaction = Config::sniffer().redaction;
+
+    let (mut packets, notes) = buffered_dump();
+    for packet in &mut packets {
+        packet.redact(&redaction);
+    }
+
+    let dump = Dump {
+        version: DUMP_VERSION,
+        header: None,
+        packets: &packets,
+        notes,
+        markers: CAPTURE_MARKERS.lock().recover().clone(),
+    };
+
+    save_dump(&PathBuf::from(file_path), &dump).map_err(|error| {
+        warn!("Failed to save the redacted packet dump: {}", error);
+        "capability.sniffer.dumps.write-failed"
+    })
+}
+
+/// Saves the buffered packets, their notes, and the markers to the dumps directory.
+///
+/// This keeps a live capture when the launcher exits.\
+/// Returns the path of the dump, or `None` if no packets are buffered.
+pub fn save_buffer() -> anyhow::Result<Option<PathBuf>> {
+    let (packets, notes) = buffered_dump();
+    if packets.is_empty() {
+        return Ok(None);
+    }
+
+    let dump = Dump {
+        version: DUMP_VERSION,
+        header: None,
+        packets: &packets,
+        notes,
+        markers: CAPTURE_MARKERS.lock().recover().clone(),
+    };
+
+    let encoded = serde_json::to_string_pretty(&dump)?;
+    let compress = Config::sniffer().compress_dumps;
+    let extension = if compress { "json.gz" } else { "json" };
+
+    let (path, file) = create_dump_file(&dumps_dir()?, extension)?;
+    write_encoded(file, encoded, compress)?;
+
+    Ok(Some(path))
+}
+
+/// Writes a dump to the given path.
+///
+/// If the path ends with `.gz`, the dump is compressed with gzip.
+fn save_dump<P: Serialize>(path: &PathBuf, dump: &Dump<P>) -> anyhow::Result<()> {
+    let encoded = serde_json::to_string_pretty(dump)?;
+
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        utils::write_compressed_file(path, encoded)
+    } else {
+        utils::write_file(path, encoded)
+    }
+}
+
+/// The format to export a packet's raw data in.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// The raw binary data.
+    Binary,
+
+    /// The data encoded as a standard Base64 string.
+    Base64,
+}
+
+/// Writes the raw data of a buffered packet to the given path.
+#[tauri::command]
+pub fn sniffer__export_packet(index: u32, path: String, format: ExportFormat) -> MaybeError<()> {
+    let Some(packet) = buffered_packet(index) else {
+        return Err("capability.sniffer.error.bad-index");
+    };
+
+    // Encode the packet's data.
+    let data = match format {
+        ExportFormat::Binary => packet.binary,
+        ExportFormat::Base64 => utils::base64_encode(&packet.binary).into_bytes(),
+    };
+
+    fs::write(path, data).map_err(|error| {
+        warn!("Failed to export packet {}: {}", index, error);
+        "capability.sniffer.error.export-failed"
+    })
+}
+
+/// Metadata about the session that a dump was captured in.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpHeader {
+    /// The ID of the profile selected during the capture.
+    pub profile_id: String,
+
+    /// The name of the profile selected during the capture.
+    pub profile_name: String,
+
+    /// The game version of the profile selected during the capture.
+    pub version: String,
+}
+
+impl DumpHeader {
+    /// Creates a header from the currently selected profile.
+    ///
+    /// Returns `None` if no profile is selected.
+    pub async fn from_selected() -> Option<Self> {
+        let profile_id = GLOBAL_STATE.read().recover().selected_profile.clone()?;
+
+        let game_manager = GameManager::get().read().await;
+        let profile = game_manager.get_profile(profile_id)?;
+
+        Some(Self {
+            profile_id: profile.id,
+            profile_name: profile.name,
+            version: profile.version.version,
+        })
+    }
+}
+
+/// The current version of the dump format.
+///
+/// Dumps without a version are version 1.
+const DUMP_VERSION: u64 = 2;
+
+/// A packet dump, as written to the file system.
+///
+/// Dumps created before headers existed are a plain list of packets.\
+/// Dumps saved from the visualizer contain `VisualPacket`s instead of `Packet`s.
+#[derive(Serialize)]
+struct Dump<'a, P: Serialize> {
+    /// The version of the dump format.
+    version: u64,
+
+    /// Metadata about the capture session.
+    header: Option<DumpHeader>,
+
+    /// The captured packets.
+    packets: &'a [P],
+
+    /// The user's notes on the packets, keyed by packet index.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    notes: BTreeMap<u32, String>,
+
+    /// The user's markers on the capture timeline.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    markers: Vec<Marker>,
+}
+
+/// A packet dump, as loaded for the frontend.
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedDump {
+    /// Metadata about the capture session.
+    ///
+    /// This is `None` for older dumps.
+    header: Option<DumpHeader>,
+
+    /// The loaded packets.
+    packets: Vec<VisualPacket>,
+
+    /// The user's notes on the loaded packets.
+    ///
+    /// These are keyed by the index of the packet in the packet buffer.
+    notes: BTreeMap<u32, String>,
+
+    /// The user's markers on the capture timeline.
+    markers: Vec<Marker>,
+
+    /// The number of malformed packets which were skipped.
+    skipped: usize,
+}
+
+/// A dump read from the file system, before its packets are parsed.
+struct RawDump {
+    header: Option<DumpHeader>,
+    packets: Vec<Value>,
+    notes: BTreeMap<u32, String>,
+    markers: Vec<Marker>,
+}
+
+/// Reads the data of a dump file.
+///
+/// If the file is compressed with gzip, it is decompressed.
+fn read_dump_data(file_path: &PathBuf) -> Result<Vec<u8>, &'static str> {
+    // Read the file.
+    let data = match utils::read_file(file_path) {
         Ok(data) => data,
         Err(_) => return Err("Failed to read the file."),
     };
-    if let Ok(reader) = PcapReader::new(&file) {
-        return read_pcap(reader);
+
+    // Decompress the data if it is compressed.
+    // This must happen before checking the format of the data.
+    if utils::is_gzip(&data) {
+        match utils::decompress(&data) {
+            Ok(data) => Ok(data),
+            Err(_) => Err("Failed to decompress the file."),
+        }
+    } else {
+        Ok(data)
     }
+}
 
-    // Otherwise, try treating the data as plain-text JSON.
-    let json_data = match serde_json::from_slice::<Vec<Value>>(&data) {
+/// Upgrades the JSON data of a dump to the current format.
+///
+/// Version 1 dumps are either a plain list of packets, or an object
+/// containing a header, the packets, and optionally notes & markers.
+fn upgrade_dump(data: Value) -> Result<Map<String, Value>, &'static str> {
+    let mut dump = match data {
+        // The oldest dumps are a plain list of packets.
+        Value::Array(packets) => Map::from_iter([("packets".to_string(), Value::Array(packets))]),
+        Value::Object(dump) => dump,
+        _ => return Err("Invalid JSON data provided"),
+    };
+
+    let version = dump.get("version").and_then(Value::as_u64).unwrap_or(1);
+    if version > DUMP_VERSION {
+        return Err("The dump was saved by a newer version of the launcher.");
+    }
+
+    // Version 2 only added the version itself, so there is nothing else to change.
+    dump.insert("version".to_string(), Value::from(DUMP_VERSION));
+
+    Ok(dump)
+}
+
+/// Parses the data of a JSON dump.
+///
+/// Older dumps are upgraded to the current format first.
+fn parse_dump(data: &[u8]) -> Result<RawDump, &'static str> {
+    let json_data = match serde_json::from_slice::<Value>(data) {
         Ok(data) => data,
         Err(_) => return Err("Invalid JSON data provided"),
     };
 
+    let mut dump = upgrade_dump(json_data)?;
+    let header = dump
+        .remove("header")
+        .and_then(|header| serde_json::from_value::<DumpHeader>(header).ok());
+    let notes = dump
+        .remove("notes")
+        .and_then(|notes| serde_json::from_value::<BTreeMap<u32, String>>(notes).ok())
+        .unwrap_or_default();
+    let markers = dump
+        .remove("markers")
+        .and_then(|markers| serde_json::from_value::<Vec<Marker>>(markers).ok())
+        .unwrap_or_default();
+
+    let Some(Value::Array(packets)) = dump.remove("packets") else {
+        return Err("Invalid JSON data provided");
+    };
+
+    Ok(RawDump {
+        header,
+        packets,
+        notes,
+        markers,
+    })
+}
+
+/// Reads and parses the selected file for packets.
+///
+/// If the file is in a JSON file, it will try to be parsed as a `Packet` or `VisualPacket`.
+///
+/// If the file is in `pcap` format, it will try to be parsed as a `Packet`.
+///
+/// If the file is compressed with gzip, it is decompressed first.\
+/// The loaded packets, their notes, and the markers are added to the packet buffer.
+#[tauri::command]
+pub fn sniffer__load(file_path: String) -> Result<LoadedDump, &'static str> {
+    let data = read_dump_data(&PathBuf::from(file_path))?;
+
+    // Check if the data is a packet capture.
+    if let Ok(reader) = PcapReader::new(data.as_slice()) {
+        return Ok(LoadedDump {
+            header: None,
+            packets: buffer_packets(read_pcap(reader)?),
+            notes: BTreeMap::new(),
+            markers: Vec::new(),
+            skipped: 0,
+        });
+    }
+
+    // Otherwise, try treating the data as plain-text JSON.
+    let dump = parse_dump(&data)?;
+
+    // The loaded packets are appended to the buffer, so their notes are offset too.
+    let base_index = PACKET_BUFFER.lock().recover().next_index;
+    let (packets, skipped) = parse_packets(dump.packets)?;
+    let packets = buffer_packets(packets);
+
+    let notes = dump
+        .notes
+        .into_iter()
+        .map(|(index, note)| (base_index + index, note))
+        .collect::<BTreeMap<_, _>>();
+    PACKET_NOTES.lock().recover().extend(notes.clone());
+    CAPTURE_MARKERS
+        .lock()
+        .recover()
+        .extend(dump.markers.clone());
+
+    Ok(LoadedDump {
+        header: dump.header,
+        packets,
+        notes,
+        markers: dump.markers,
+        skipped,
+    })
+}
+
+/// Converts a dump of `Packet`s into a dump of `VisualPacket`s.
+///
+/// `VisualPacket` dumps load faster, as their packets are already decoded.\
+/// The packets keep their order, and are indexed by their position in the new dump.
+///
+/// Returns the number of malformed packets which were skipped.
+#[tauri::command]
+pub fn sniffer__upgrade_dump(input: String, output: String) -> MaybeError<usize> {
+    let data = read_dump_data(&PathBuf::from(input))?;
+    let dump = parse_dump(&data)?;
+
+    // Decode the packets.
+    let (mut packets, skipped) = parse_packets(dump.packets)?;
+    for (index, packet) in packets.iter_mut().enumerate() {
+        packet.index = index as u32;
+    }
+
+    let upgraded = Dump {
+        version: DUMP_VERSION,
+        header: dump.header,
+        packets: &packets,
+        notes: dump.notes,
+        markers: dump.markers,
+    };
+
+    save_dump(&PathBuf::from(output), &upgraded).map_err(|error| {
+        warn!("Failed to save the upgraded packet dump: {}", error);
+        "capability.sniffer.dumps.write-failed"
+    })?;
+
+    Ok(skipped)
+}
+
+/// Adds the loaded packets to the packet buffer.
+///
+/// Loaded packets are appended to the visualizer's packet list, so they are re-indexed.
+fn buffer_packets(packets: Vec<VisualPacket>) -> Vec<VisualPacket> {
+    let capacity = Config::sniffer().buffer_capacity;
+    packets
+        .into_iter()
+        .map(|packet| buffer_packet(packet, capacity))
+        .collect()
+}
+
+/// A JSON value parsed as either kind of packet.
+enum ParsedPacket {
+    /// A packet which was already decoded.
+    Visual(VisualPacket),
+
+    /// A packet which still needs to be decoded.
+    Raw(Packet),
+}
+
+/// Parses a list of JSON values as packets.
+///
+/// Each value is parsed on its own, so dumps may mix `Packet`s and `VisualPacket`s.\
+/// A value is parsed as a `VisualPacket` first, then as a `Packet`.
+///
+/// Malformed values are skipped, and the number of skipped values is returned.\
+/// If every value is malformed, this returns an error.
+fn parse_packets(json_data: Vec<Value>) -> Result<(Vec<VisualPacket>, usize), &'static str> {
     // If the data is empty, return nothing now.
     if json_data.is_empty() {
-        return Ok(vec![]);
-    }
-
-    // Check if the first element contains a 'binary' field.
-    let first = &json_data[0];
-    match first.get("binary") {
-        Some(Value::String(_)) => Ok(json_data
-            .iter()
-            .map(|value| serde_json::from_value::<VisualPacket>(value.clone()).unwrap())
-            .collect::<Vec<VisualPacket>>()),
-        None | Some(Value::Null) => read_json(
-            json_data
-                .iter()
-                .map(|value| serde_json::from_value::<Packet>(value.clone()).unwrap())
-                .collect::<Vec<Packet>>(),
-        ),
-        _ => Err("Invalid JSON data provided"),
+        return Ok((vec![], 0));
+    }
+
+    let total = json_data.len();
+    let parsed = json_data
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, value)| parse_packet(index, value))
+        .collect::<Vec<_>>();
+
+    // The first raw packet serves as the base time for the raw packets.
+    let base_time = parsed
+        .iter()
+        .find_map(|packet| match packet {
+            ParsedPacket::Raw(packet) => Some(packet.received),
+            ParsedPacket::Visual(_) => None,
+        })
+        .unwrap_or_default();
+
+    let packets = parsed
+        .into_iter()
+        .filter_map(|packet| match packet {
+            ParsedPacket::Visual(packet) => Some(packet),
+            ParsedPacket::Raw(packet) => decode_packet(packet, base_time),
+        })
+        .collect::<Vec<_>>();
+
+    if packets.is_empty() {
+        return Err("Invalid JSON data provided");
+    }
+
+    Ok((packets, total - packets.len()))
+}
+
+/// Parses a single JSON value as either kind of packet.
+///
+/// Returns `None` if the value is neither a `VisualPacket` nor a `Packet`.
+fn parse_packet(index: usize, value: Value) -> Option<ParsedPacket> {
+    // Only visual packets contain the 'binary' field.
+    if value.get("binary").is_some_and(Value::is_string) {
+        return match serde_json::from_value::<VisualPacket>(value) {
+            Ok(packet) => Some(ParsedPacket::Visual(packet)),
+            Err(error) => {
+                warn!("Skipping malformed packet at index {}: {}", index, error);
+                None
+            }
+        };
+    }
+
+    match serde_json::from_value::<Packet>(value) {
+        Ok(packet) => Some(ParsedPacket::Raw(packet)),
+        Err(error) => {
+            warn!("Skipping malformed packet at index {}: {}", index, error);
+            None
+        }
     }
 }
 
@@ -402,41 +1626,37 @@ fn read_pcap<R: Read>(_: PcapReader<R>) -> Result<Vec<VisualPacket>, &'static st
     Err("Not implemented")
 }
 
-/// Reads the JSON data as a list of packets.
+/// Decodes a packet into a `VisualPacket` with `protoshark`.
 ///
-/// This method exists to run `protoshark` on the data.
-fn read_json(data: Vec<Packet>) -> Result<Vec<VisualPacket>, &'static str> {
-    let mut packets = Vec::new();
-
-    // Get the first packet.
-    // This will serve as the base time for the packets.
-    let Some(first) = data.first() else {
-        // This means no packets exist.
-        // We can just return an empty array.
-        return Ok(packets);
-    };
-    let base_time = first.received;
-
-    for packet in data {
-        // Run `protoshark` to decode the packet.
+/// The packet's time is offset from `base_time`.\
+/// Packets without a payload (metadata-only) are not decoded.\
+/// Returns `None` if the packet couldn't be decoded.
+fn decode_packet(packet: Packet, base_time: u128) -> Option<VisualPacket> {
+    let length = packet.length();
+
+    // Run `protoshark` to decode the packet.
+    let data = if packet.data.is_empty() {
+        String::new()
+    } else {
         let Ok(decoded) = protoshark::decode(&packet.data) else {
             warn!("Failed to decode packet: {}", packet.id);
-            continue;
+            return None;
         };
 
-        packets.push(VisualPacket {
-            time: (packet.received - base_time) as f32,
-            source: packet.source,
-            packet_id: packet.id,
-            packet_name: packet.id.to_string(),
-            length: packet.data.len() as u64,
-            data: serde_json::to_string(&decoded).unwrap(),
-            binary: packet.data.clone(),
-            index: packets.len() as u32,
-        });
-    }
+        serde_json::to_string(&decoded).unwrap_or_default()
+    };
 
-    Ok(packets)
+    Some(VisualPacket {
+        time: packet.received.saturating_sub(base_time) as f32,
+        timestamp: packet.timestamp,
+        source: packet.source,
+        packet_id: packet.id,
+        packet_name: packet.id.to_string(),
+        length,
+        data,
+        binary: packet.data,
+        index: 0,
+    })
 }
 
 mod src_string {
@@ -456,3 +1676,270 @@ mod src_string {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Creates a raw packet without a payload, so it doesn't need to be decoded.
+    fn raw_packet(id: u16, received: u128) -> Value {
+        serde_json::to_value(Packet {
+            id,
+            header: Vec::new(),
+            data: Vec::new(),
+            source: PacketSource::Client,
+            received,
+            timestamp: 0,
+            length: Some(4),
+        })
+        .unwrap()
+    }
+
+    /// Creates a packet which was already decoded.
+    fn visual_packet(id: u16) -> Value {
+        serde_json::to_value(VisualPacket {
+            packet_id: id,
+            packet_name: id.to_string(),
+            length: 2,
+            binary: vec![1, 2],
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    /// Creates a packet as it is received from the sniffer.
+    fn game_packet(id: u16) -> GamePacket {
+        GamePacket {
+            id,
+            header: Vec::new(),
+            data: vec![0; 4],
+            source: PacketSource::Client,
+        }
+    }
+
+    #[test]
+    fn the_packet_buffer_is_bounded() {
+        sniffer__clear_buffer();
+        let first = buffer_packet(VisualPacket::default(), 3);
+        sniffer__set_note(first.index, "removed".to_string()).unwrap();
+        for _ in 0..4 {
+            buffer_packet(VisualPacket::default(), 3);
+        }
+        sniffer__set_note(3, "kept".to_string()).unwrap();
+
+        // The oldest packets and their notes are removed, but indices keep counting.
+        let indices: Vec<u32> = with_buffer(|packets| packets.iter().map(|p| p.index).collect());
+        assert_eq!(indices, [2, 3, 4]);
+        assert!(buffered_packet(1).is_none());
+        assert_eq!(buffered_packet(4).unwrap().index, 4);
+        assert!(!sniffer__get_notes().contains_key(&0));
+
+        // Dumps index notes by the position of their packet.
+        let (packets, notes) = buffered_dump();
+        assert_eq!(packets.len(), 3);
+        assert_eq!(notes, BTreeMap::from([(1, "kept".to_string())]));
+
+        // A new capture starts empty, without reusing indices.
+        reset_buffer();
+        assert_eq!(buffer_packet(VisualPacket::default(), 3).index, 5);
+
+        sniffer__clear_buffer();
+        assert_eq!(buffer_packet(VisualPacket::default(), 3).index, 0);
+        sniffer__clear_buffer();
+    }
+
+    #[test]
+    fn malformed_packets_are_skipped() {
+        let values = vec![
+            raw_packet(1, 1000),
+            json!({ "id": "not a packet" }),
+            raw_packet(2, 1500),
+        ];
+
+        let (packets, skipped) = parse_packets(values).unwrap();
+        let ids: Vec<u16> = packets.iter().map(|packet| packet.packet_id).collect();
+
+        assert_eq!(ids, [1, 2]);
+        assert_eq!(skipped, 1);
+    }
+
+    #[test]
+    fn only_malformed_packets_are_an_error() {
+        let values = vec![json!({ "id": "not a packet" }), json!(42)];
+
+        assert!(parse_packets(values).is_err());
+    }
+
+    #[test]
+    fn mixed_packets_are_loaded() {
+        // The first packet is a raw packet, which used to decide the type of the whole dump.
+        let values = vec![raw_packet(1, 1000), visual_packet(2), raw_packet(3, 1250)];
+
+        let (packets, skipped) = parse_packets(values).unwrap();
+        let ids: Vec<u16> = packets.iter().map(|packet| packet.packet_id).collect();
+
+        assert_eq!(ids, [1, 2, 3]);
+        assert_eq!(skipped, 0);
+
+        // Raw packets are timed from the first raw packet.
+        assert_eq!(packets[0].time, 0.0);
+        assert_eq!(packets[2].time, 250.0);
+        assert_eq!(packets[2].length, 4);
+
+        // Visual packets are kept as they are.
+        assert_eq!(packets[1].binary, [1, 2]);
+    }
+
+    #[test]
+    fn bare_packet_lists_are_upgraded() {
+        let data = json!([raw_packet(1, 1000), raw_packet(2, 1500)]);
+
+        let dump = upgrade_dump(data).unwrap();
+        assert_eq!(dump["version"], DUMP_VERSION);
+        assert_eq!(dump["packets"].as_array().unwrap().len(), 2);
+
+        let dump = parse_dump(json!(dump).to_string().as_bytes()).unwrap();
+        assert!(dump.header.is_none());
+        assert_eq!(dump.packets.len(), 2);
+    }
+
+    #[test]
+    fn unversioned_dumps_are_upgraded() {
+        let data = json!({
+            "header": {
+                "profileId": "profile",
+                "profileName": "Profile",
+                "version": "OSRELWin5.0.0"
+            },
+            "packets": [raw_packet(1, 1000)]
+        });
+
+        let dump = upgrade_dump(data).unwrap();
+        assert_eq!(dump["version"], DUMP_VERSION);
+        assert_eq!(dump["packets"].as_array().unwrap().len(), 1);
+
+        let dump = parse_dump(json!(dump).to_string().as_bytes()).unwrap();
+        assert_eq!(dump.header.unwrap().profile_id, "profile");
+        assert_eq!(dump.packets, [raw_packet(1, 1000)]);
+    }
+
+    #[test]
+    fn newer_dumps_are_rejected() {
+        let data = json!({ "version": DUMP_VERSION + 1, "packets": [] });
+
+        assert!(upgrade_dump(data).is_err());
+    }
+
+    #[tokio::test]
+    async fn packets_are_forwarded_to_the_reader() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (queue_tx, mut queue_rx) = tokio::sync::mpsc::channel(1);
+        let forwarder = tokio::spawn(forward_packets(rx, queue_tx, 16, Overflow::DropOldest));
+
+        // Read the packets on their own task, like the CLI does.
+        let reader = tokio::spawn(async move {
+            let mut ids = Vec::new();
+            while let Some(packet) = queue_rx.recv().await {
+                ids.push(packet.id);
+            }
+
+            ids
+        });
+
+        for id in 1..=5 {
+            tx.send(game_packet(id)).unwrap();
+        }
+
+        // Stopping the sniffer closes its channel, which stops both tasks.
+        drop(tx);
+
+        let timeout = Duration::from_secs(5);
+        tokio::time::timeout(timeout, forwarder)
+            .await
+            .unwrap()
+            .unwrap();
+        let ids = tokio::time::timeout(timeout, reader)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(ids, [1, 2, 3, 4, 5]);
+    }
+
+    /// Creates the state of a CLI packet reader.
+    fn packet_reader(max_packets: Option<usize>) -> PacketReader {
+        PacketReader {
+            packets: Arc::new(Mutex::new(Vec::new())),
+            log_enabled: Arc::new(AtomicBool::new(false)),
+            limit_reached: Arc::new(Notify::new()),
+            max_packets,
+            metadata_only: false,
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn the_cli_reader_keeps_packets_sent_before_shutdown() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let (queue_tx, queue_rx) = tokio::sync::mpsc::channel(1);
+
+        // Spawn the forwarding and reading tasks on the shared runtime, like the CLI does.
+        let reader = packet_reader(None);
+        let packets = reader.packets.clone();
+        tokio::spawn(forward_packets(rx, queue_tx, 16, Overflow::DropOldest));
+        let reader = tokio::spawn(read_packets(queue_rx, reader));
+
+        for id in 1..=3 {
+            tx.send(game_packet(id)).unwrap();
+        }
+
+        // Shutting down the sniffer closes its channel.
+        // The reader still drains the queued packets before it finishes.
+        drop(tx);
+        tokio::time::timeout(Duration::from_secs(5), reader)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let packets = packets.lock().await;
+        let ids: Vec<u16> = packets.iter().map(|packet| packet.id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn the_cli_reader_stops_at_the_packet_limit() {
+        let (tx, rx) = tokio::sync::mpsc::channel(8);
+        let reader = packet_reader(Some(2));
+        let packets = reader.packets.clone();
+        let limit_reached = reader.limit_reached.clone();
+
+        for id in 1..=3 {
+            tx.send(game_packet(id)).await.unwrap();
+        }
+
+        let reader = tokio::spawn(read_packets(rx, reader));
+        tokio::time::timeout(Duration::from_secs(5), limit_reached.notified())
+            .await
+            .unwrap();
+        reader.await.unwrap();
+
+        assert_eq!(packets.lock().await.len(), 2);
+    }
+
+    #[test]
+    fn dumps_written_back_to_back_are_kept() {
+        let dumps_dir = tempfile::tempdir().unwrap();
+
+        // The second dump is usually named in the same millisecond as the first.
+        let (first, file) = create_dump_file(dumps_dir.path(), "json").unwrap();
+        write_encoded(file, "[]".to_string(), false).unwrap();
+        let (second, file) = create_dump_file(dumps_dir.path(), "json").unwrap();
+        write_encoded(file, "[]".to_string(), false).unwrap();
+
+        assert_ne!(first, second);
+        assert_eq!(fs::read_to_string(&first).unwrap(), "[]");
+        assert!(first.is_file());
+        assert!(second.is_file());
+        assert_eq!(fs::read_dir(dumps_dir.path()).unwrap().count(), 2);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::app::game::GameManager;
+use crate::database;
+use clap::ArgMatches;
+use log::{info, warn};
+use serde::Serialize;
+
+/// Parses the command tree for `db`.
+pub async fn db(matches: &ArgMatches) {
+    let (command, sub_matches) = matches.subcommand().unwrap();
+    let json = sub_matches.get_flag("json");
+
+    match command {
+        "stats" => stats(json).await,
+        "profiles" => profiles(json).await,
+        "versions" => versions(json).await,
+        "tools" => tools(json).await,
+        "mods" => mods(json).await,
+        "optimize" => optimize(json).await,
+        _ => unimplemented!(),
+    }
+}
+
+/// Prints the value as JSON.
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{}", json),
+        Err(error) => warn!("Failed to serialize the output: {}", error),
+    }
+}
+
+/// Prints the row counts of each table and the size of the database file.
+async fn stats(json: bool) {
+    let stats = match database::stats().await {
+        Ok(stats) => stats,
+        Err(error) => {
+            warn!("{} {}", t!("database.query-failed"), error);
+            return;
+        }
+    };
+
+    if json {
+        print_json(&stats);
+        return;
+    }
+
+    println!("File size: {} bytes", stats.file_size);
+    for (table, rows) in &stats.tables {
+        println!("{:<12} {} rows", table, rows);
+    }
+}
+
+/// Lists all profiles.
+async fn profiles(json: bool) {
+    let game_manager = GameManager::get().read().await;
+    if json {
+        print_json(&game_manager.profiles);
+        return;
+    }
+
+    for profile in &game_manager.profiles {
+        println!(
+            "{:<18} {:<24} {:<10} {} tools, {} mods",
+            profile.id,
+            profile.name,
+            profile.version.version,
+            profile.tools.len(),
+            profile.mods.len()
+        );
+    }
+}
+
+/// Lists all known game versions.
+async fn versions(json: bool) {
+    let game_manager = GameManager::get().read().await;
+    if json {
+        print_json(&game_manager.versions);
+        return;
+    }
+
+    for version in &game_manager.versions {
+        println!("{:<24} {}", version.version, version.path);
+    }
+}
+
+/// Lists all tools.
+async fn tools(json: bool) {
+    let game_manager = GameManager::get().read().await;
+    if json {
+        print_json(&game_manager.tools);
+        return;
+    }
+
+    for tool in &game_manager.tools {
+        println!("{:<18} {:<24} {}", tool.id, tool.name, tool.path);
+    }
+}
+
+/// Lists all mods.
+async fn mods(json: bool) {
+    let game_manager = GameManager::get().read().await;
+    if json {
+        print_json(&game_manager.mods);
+        return;
+    }
+
+    for r#mod in &game_manager.mods {
+        println!(
+            "{:<18} {:<24} {:<10} {}",
+            r#mod.id, r#mod.name, r#mod.version, r#mod.tool.name
+        );
+    }
+}
+
+/// Vacuums and optimizes the database.
+async fn optimize(json: bool) {
+    match database::optimize().await {
+        Ok(result) if json => print_json(&result),
+        Ok(result) => info!(
+            "Optimized the database: {} bytes -> {} bytes.",
+            result.size_before, result.size_after
+        ),
+        Err(error) => warn!("{} {}", t!("database.optimize-failed"), error),
+    }
+}
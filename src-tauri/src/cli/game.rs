@@ -1,5 +1,8 @@
 use crate::app::game;
 use crate::app::game::{GameManager, Profile};
+use crate::state;
+use crate::utils::{Recover, Translate};
+use crate::GLOBAL_STATE;
 use clap::ArgMatches;
 use dialoguer::Input;
 use dialoguer::{theme::ColorfulTheme, Select};
@@ -35,8 +38,8 @@ async fn locate_version() {
     };
 
     // Locate the game.
-    if let Err(error) = game::locate_game(path).await {
-        warn!("{}", t!(error));
+    if let Err(error) = game::locate_game(path).await.translated() {
+        warn!("{}", error);
     }
 }
 
@@ -44,6 +47,7 @@ async fn locate_version() {
 pub async fn profile(matches: &ArgMatches) {
     match matches.subcommand().unwrap() {
         ("new", _) => new_profile().await,
+        ("select", _) => select_profile().await,
         _ => unimplemented!(),
     }
 }
@@ -86,7 +90,40 @@ async fn new_profile() {
     };
 
     // Save the profile.
-    if let Err(error) = game_manager.save_profile(profile).await {
-        warn!("{} {}", t!("launcher.error.profile.unknown"), error);
+    let profile = match game_manager.save_profile(profile).await {
+        Ok(profile) => profile,
+        Err(error) => {
+            warn!("{} {}", t!("launcher.error.profile.unknown"), error);
+            return;
+        }
+    };
+
+    // If no profile is selected, select the new profile.
+    if GLOBAL_STATE.read().recover().selected_profile.is_none() {
+        state::select_profile(profile);
     }
 }
+
+/// Prompts the user to select an existing profile.
+async fn select_profile() {
+    let game_manager = GameManager::get().read().await;
+    let profiles = &game_manager
+        .profiles
+        .iter()
+        .map(|p| p.name.clone())
+        .collect::<Vec<String>>();
+
+    // Ask the user to select a profile.
+    let Ok(index) = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(t!("cli.game.profile.select.prompt"))
+        .default(0)
+        .items(profiles)
+        .interact()
+    else {
+        warn!("{}", t!("launcher.error.profile.bad-id"));
+        return;
+    };
+
+    // Set the selected profile.
+    state::select_profile(game_manager.profiles[index].clone());
+}
@@ -1,5 +1,7 @@
+mod db;
 mod game;
 
+use crate::app;
 use crate::app::game as app_game;
 use crate::capabilities;
 use clap::ArgMatches;
@@ -8,10 +10,26 @@ use log::info;
 /// Command-line interface command handler.
 pub async fn run(matches: Option<(&str, &ArgMatches)>) {
     match matches {
-        Some(("sniff", _)) => {
+        Some(("sniff", sub_matches)) => {
             info!("Type 'help' for a list of commands.");
-            capabilities::sniffer::run_cli().await;
+
+            let filter = sub_matches.get_one::<String>("filter").cloned();
+            let max_packets = sub_matches.get_one::<usize>("max-packets").copied();
+            capabilities::sniffer::run_cli(filter, max_packets).await;
+        }
+        #[cfg(feature = "packet-injector")]
+        Some(("inject", _)) => match capabilities::injector::verify_capture(10).await {
+            Ok(packets) => info!("Captured {} of 10 injected packets.", packets.len()),
+            Err(error) => warn!("Failed to verify packet capture: {}", error),
+        },
+        Some(("version", _)) => {
+            let info = app::app_info();
+            println!("ys-compass {} ({})", info.version, info.target);
+            println!("Build: {}", if info.debug { "debug" } else { "release" });
+            println!("OS: {}", info.os);
+            println!("Elevated: {}", info.elevated);
         }
+        Some(("db", sub_matches)) => db::db(sub_matches).await,
         Some(("game", sub_matches)) => match sub_matches.subcommand().unwrap() {
             ("version", sub_matches) => game::version(sub_matches).await,
             ("profile", sub_matches) => game::profile(sub_matches).await,
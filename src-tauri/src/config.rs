@@ -1,9 +1,18 @@
+use crate::utils::Recover;
 use crate::{utils, SYSTEM_LANGUAGE};
 use anyhow::{anyhow, Result};
+use reqwest::Url;
 use serde::{Deserialize, Serialize};
+use std::fs;
 use std::fs::File;
+use std::path::PathBuf;
 use std::sync::{Mutex, MutexGuard, OnceLock};
 
+/// The configuration file used instead of the default one.
+///
+/// This is set with the `--config` flag.
+static CONFIG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
 #[derive(PartialEq, Copy, Clone)]
 pub enum Language {
     English,
@@ -36,15 +45,49 @@ impl Language {
     }
 }
 
-/// Attempts to deserialize the configuration file.
+/// Uses a different configuration file for this run.
 ///
-/// If it doesn't exist, the default configuration for the language will be used.
-pub fn deserialize(language: Language) -> Result<Config> {
+/// This must be called before the configuration is first loaded.\
+/// The file must be readable if it exists, otherwise its directory is created.
+pub fn set_config_path(path: PathBuf) -> Result<()> {
+    if path.exists() {
+        if let Err(error) = File::open(&path) {
+            return Err(anyhow!("Unable to read the configuration file: {error}"));
+        }
+    } else if let Some(parent) = path.parent() {
+        if let Err(error) = fs::create_dir_all(parent) {
+            return Err(anyhow!("Unable to create the config directory: {error}"));
+        }
+    }
+
+    if CONFIG_PATH.set(path).is_err() {
+        return Err(anyhow!("The configuration file was already set."));
+    }
+
+    Ok(())
+}
+
+/// Returns the path to the configuration file.
+///
+/// This is `config.yml` in the app data directory, unless it was overridden.
+pub fn config_path() -> Result<PathBuf> {
+    if let Some(path) = CONFIG_PATH.get() {
+        return Ok(path.clone());
+    }
+
     // Resolve the app data directory.
     let Ok(app_data_dir) = utils::app_data_dir() else {
         return Err(anyhow!("Failed to resolve app data directory."));
     };
-    let config_path = app_data_dir.join("config.yml");
+
+    Ok(app_data_dir.join("config.yml"))
+}
+
+/// Attempts to deserialize the configuration file.
+///
+/// If it doesn't exist, the default configuration for the language will be used.
+pub fn deserialize(language: Language) -> Result<Config> {
+    let config_path = config_path()?;
 
     // If the config file doesn't exist, save the default one.
     if !config_path.exists() {
@@ -55,16 +98,15 @@ pub fn deserialize(language: Language) -> Result<Config> {
     }
 
     // Otherwise, deserialize the configuration file.
-    Ok(serde_yml::from_reader(File::open(config_path)?)?)
+    let config: Config = serde_yml::from_reader(File::open(config_path)?)?;
+    config.validate()?;
+
+    Ok(config)
 }
 
 /// Saves the configuration to the file.
 pub fn save_config(config: &Config) -> Result<()> {
-    // Resolve the app data directory.
-    let Ok(app_data_dir) = utils::app_config_dir() else {
-        return Err(anyhow!("Failed to resolve app data directory."));
-    };
-    let config_path = app_data_dir.join("config.yml");
+    let config_path = config_path()?;
 
     // Save the configuration to the file.
     serde_yml::to_writer(File::create(config_path)?, config)?;
@@ -114,12 +156,13 @@ pub struct Config {
 impl Config {
     /// Fetches a mutable configuration.
     ///
-    /// Deserializes the configuration if it hasn't been done yet.
+    /// Deserializes the configuration if it hasn't been done yet.\
+    /// If a thread panicked while holding the lock, the configuration is recovered.
     pub fn get<'a>() -> MutexGuard<'a, Config> {
         static CONFIG: OnceLock<Mutex<Config>> = OnceLock::new();
         let mutex = CONFIG.get_or_init(|| Mutex::new(deserialize(*SYSTEM_LANGUAGE).unwrap()));
 
-        mutex.lock().unwrap()
+        mutex.lock().recover()
     }
 
     /// Creates a clone of the configuration.
@@ -127,6 +170,47 @@ impl Config {
         Config::get().clone()
     }
 
+    /// Creates a clone of the launcher configuration.
+    ///
+    /// The configuration lock is only held while cloning.
+    pub fn launcher() -> Launcher {
+        Config::get().launcher.clone()
+    }
+
+    /// Creates a clone of the game configuration.
+    ///
+    /// The configuration lock is only held while cloning.
+    pub fn game() -> Game {
+        Config::get().game.clone()
+    }
+
+    /// Creates a clone of the sniffer configuration.
+    ///
+    /// The configuration lock is only held while cloning.
+    pub fn sniffer() -> Sniffer {
+        Config::get().sniffer.clone()
+    }
+
+    /// Checks the configuration for values which can't be used.
+    pub fn validate(&self) -> Result<()> {
+        // The game information endpoints must be valid URLs.
+        let launcher = &self.launcher;
+        let endpoints =
+            std::iter::once(&launcher.game_info_url).chain(launcher.game_info_fallback_urls.iter());
+
+        for endpoint in endpoints {
+            if let Err(error) = Url::parse(endpoint) {
+                return Err(anyhow!(
+                    "Invalid game information URL '{}': {}",
+                    endpoint,
+                    error
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns the default language.
     ///
     /// This is based on the system's language.
@@ -148,16 +232,151 @@ pub struct Launcher {
     /// The launcher might sometimes ask for
     /// elevation regardless to open the game.
     pub always_elevate: bool,
+
+    /// The retention policy for packet dumps.
+    ///
+    /// If set, old packet dumps are deleted when the launcher starts.
+    #[serde(default)]
+    pub dump_retention: Option<DumpRetention>,
+
+    /// The name of the appearance preset to use.
+    ///
+    /// Presets change the background, accent color, and splash screen.
+    #[serde(default = "Launcher::default_appearance_preset")]
+    pub appearance_preset: String,
+
+    /// The URL of the basic game information API.
+    ///
+    /// This is used for fetching the launcher background.\
+    /// Change this to use the API of a different region.
+    #[serde(default = "Launcher::default_game_info_url")]
+    pub game_info_url: String,
+
+    /// Additional URLs of the basic game information API.
+    ///
+    /// These are tried in order if the main URL fails.
+    #[serde(default)]
+    pub game_info_fallback_urls: Vec<String>,
+
+    /// The ID of the game in the basic game information API.
+    ///
+    /// This differs between regions.
+    #[serde(default = "Launcher::default_game_id")]
+    pub game_id: String,
+
+    /// Whether to watch the mods directory for new mods.
+    ///
+    /// New `.dll` and `.zip` files are offered to be registered.
+    #[serde(default)]
+    pub watch_mods: bool,
+
+    /// The configuration used for running the game with Wine.
+    #[serde(default)]
+    pub wine: Wine,
+
+    /// The splash screen shown while the launcher starts.
+    #[serde(default)]
+    pub splash: Splash,
+}
+
+impl Launcher {
+    /// Returns the default appearance preset.
+    fn default_appearance_preset() -> String {
+        "default".to_string()
+    }
+
+    /// Returns the default basic game information URL.
+    fn default_game_info_url() -> String {
+        dotenv!("BASIC_GAME_INFO_URL").to_string()
+    }
+
+    /// Returns the default game ID.
+    fn default_game_id() -> String {
+        dotenv!("GAME_ID").to_string()
+    }
 }
 
 impl Default for Launcher {
     fn default() -> Self {
         Launcher {
             always_elevate: true,
+            dump_retention: None,
+            appearance_preset: Launcher::default_appearance_preset(),
+            game_info_url: Launcher::default_game_info_url(),
+            game_info_fallback_urls: Vec::new(),
+            game_id: Launcher::default_game_id(),
+            watch_mods: false,
+            wine: Wine::default(),
+            splash: Splash::default(),
         }
     }
 }
 
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Splash {
+    /// Whether to show the splash screen.
+    #[serde(default = "Splash::default_enabled")]
+    pub enabled: bool,
+
+    /// The minimum time to show the splash screen for, in milliseconds.
+    ///
+    /// The splash screen stays open longer if the launcher takes longer to start.
+    #[serde(default = "Splash::default_min_duration")]
+    pub min_duration: u64,
+}
+
+impl Splash {
+    /// Returns whether the splash screen is shown by default.
+    fn default_enabled() -> bool {
+        true
+    }
+
+    /// Returns the default minimum splash screen duration.
+    fn default_min_duration() -> u64 {
+        1000
+    }
+}
+
+impl Default for Splash {
+    fn default() -> Self {
+        Splash {
+            enabled: Splash::default_enabled(),
+            min_duration: Splash::default_min_duration(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, PartialEq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct Wine {
+    /// The path to the Wine or Proton executable.
+    ///
+    /// If unset, the first detected runner is used.
+    #[serde(default)]
+    pub runner: Option<String>,
+
+    /// The Wine prefixes known to the launcher.
+    ///
+    /// Prefixes created by the launcher are added here.
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "kebab-case")]
+pub struct DumpRetention {
+    /// The maximum age of a packet dump, in days.
+    ///
+    /// Older dumps are deleted.
+    pub max_age_days: Option<u64>,
+
+    /// The maximum number of packet dumps to keep.
+    ///
+    /// The newest dumps are kept.
+    pub max_count: Option<usize>,
+}
+
 #[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
 #[serde(rename_all = "kebab-case")]
 pub struct Game {
@@ -168,12 +387,70 @@ pub struct Game {
     ///
     /// In most cases however, this should be set to `false`.
     pub disable_anti_cheat: bool,
+
+    /// The priority of the game process.
+    ///
+    /// This can be `normal`, `above`, `high`, or `realtime`.\
+    /// Unknown values fall back to `normal`.
+    #[serde(default = "Game::default_process_priority")]
+    pub process_priority: String,
+
+    /// The indices of the CPU cores the game can run on.
+    ///
+    /// If unset, the game can run on any core.
+    #[serde(default)]
+    pub cpu_affinity: Option<Vec<usize>>,
+
+    /// The time to wait for the anti-cheat to load and unload, in seconds.
+    ///
+    /// If this passes, the game is resumed and the launch fails.
+    #[serde(default = "Game::default_anti_cheat_timeout")]
+    pub anti_cheat_timeout: u64,
+
+    /// The name prefixes of the anti-cheat driver.
+    ///
+    /// Different regions and versions can use different drivers.\
+    /// If empty, the name the launcher was built with is used.
+    #[serde(default)]
+    pub anti_cheat_driver: Vec<String>,
+
+    /// Whether debugging tools can read the game's memory.
+    ///
+    /// Reading memory also requires the launcher to be elevated.
+    #[serde(default)]
+    pub allow_memory_reads: bool,
+}
+
+impl Game {
+    /// Returns the default process priority.
+    fn default_process_priority() -> String {
+        "normal".to_string()
+    }
+
+    /// Returns the default anti-cheat timeout.
+    fn default_anti_cheat_timeout() -> u64 {
+        30
+    }
+
+    /// Returns the name prefixes of the anti-cheat driver.
+    pub fn driver_names(&self) -> Vec<&str> {
+        if self.anti_cheat_driver.is_empty() {
+            return vec![dotenv!("GAME_DRIVER_NAME")];
+        }
+
+        self.anti_cheat_driver.iter().map(String::as_str).collect()
+    }
 }
 
 impl Default for Game {
     fn default() -> Self {
         Game {
             disable_anti_cheat: false,
+            process_priority: Game::default_process_priority(),
+            cpu_affinity: None,
+            anti_cheat_timeout: Game::default_anti_cheat_timeout(),
+            anti_cheat_driver: Vec::new(),
+            allow_memory_reads: false,
         }
     }
 }
@@ -204,6 +481,89 @@ pub struct Sniffer {
     /// This file should be readable and writable.\
     /// It contains all encryption seeds used recently.
     pub seeds_file: String,
+
+    /// Whether to compress packet dumps with gzip.
+    ///
+    /// Compressed dumps are saved as `.json.gz` files.\
+    /// They can still be loaded by the packet visualizer.
+    #[serde(default)]
+    pub compress_dumps: bool,
+
+    /// The directory where packet dumps are saved.
+    ///
+    /// This can be changed to save dumps to a larger drive.\
+    /// The directory is created if it doesn't exist.
+    #[serde(default = "Sniffer::default_dump_dir")]
+    pub dump_dir: String,
+
+    /// Whether to only keep the metadata of captured packets.
+    ///
+    /// Packet IDs, sources, lengths, and timing are kept, but their data is not.\
+    /// This is useful for sharing captures or keeping dumps small.
+    #[serde(default)]
+    pub metadata_only: bool,
+
+    /// What to redact from exported packet dumps.
+    #[serde(default)]
+    pub redaction: Redaction,
+
+    /// The number of captured packets which can wait to be processed.
+    ///
+    /// If packets arrive faster than they are processed, the queue fills up.\
+    /// Once it is full, packets are dropped instead of using more memory.
+    #[serde(default = "Sniffer::default_queue_capacity")]
+    pub queue_capacity: usize,
+
+    /// Which packets to drop once the queue is full.
+    ///
+    /// This can be `drop-oldest` or `drop-newest`.\
+    /// Unknown values fall back to `drop-oldest`.
+    #[serde(default = "Sniffer::default_queue_overflow")]
+    pub queue_overflow: String,
+
+    /// The number of packets kept for the packet visualizer.
+    ///
+    /// Once this many packets are kept, the oldest packets are removed.\
+    /// Packets are only kept while the packet visualizer is open.
+    #[serde(default = "Sniffer::default_buffer_capacity")]
+    pub buffer_capacity: usize,
+
+    /// Whether to start the sniffer when the game opens.
+    ///
+    /// The packet visualizer is opened too.\
+    /// The sniffer still stops once the game closes.
+    #[serde(default)]
+    pub auto_start_with_game: bool,
+
+    /// Whether to open the packet visualizer when the sniffer starts.
+    #[serde(default)]
+    pub open_visualizer: bool,
+
+    /// Whether to stop the sniffer when the packet visualizer is closed by the launcher.
+    #[serde(default)]
+    pub stop_with_visualizer: bool,
+}
+
+impl Sniffer {
+    /// Returns the default packet dump directory.
+    fn default_dump_dir() -> String {
+        "$APPDATA/dumps".to_string()
+    }
+
+    /// Returns the default packet queue capacity.
+    fn default_queue_capacity() -> usize {
+        10_000
+    }
+
+    /// Returns the default packet queue overflow policy.
+    fn default_queue_overflow() -> String {
+        "drop-oldest".to_string()
+    }
+
+    /// Returns the default packet buffer capacity.
+    fn default_buffer_capacity() -> usize {
+        50_000
+    }
 }
 
 impl Default for Sniffer {
@@ -213,6 +573,33 @@ impl Default for Sniffer {
             filter: "udp portrange 22101-22102".to_string(),
             server_ports: vec![22101, 22102],
             seeds_file: "$APPDATA/sniffer/known-seeds.txt".to_string(),
+            compress_dumps: false,
+            dump_dir: Sniffer::default_dump_dir(),
+            metadata_only: false,
+            redaction: Redaction::default(),
+            queue_capacity: Sniffer::default_queue_capacity(),
+            queue_overflow: Sniffer::default_queue_overflow(),
+            buffer_capacity: Sniffer::default_buffer_capacity(),
+            auto_start_with_game: false,
+            open_visualizer: false,
+            stop_with_visualizer: false,
         }
     }
 }
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct Redaction {
+    /// The IDs of packets to redact entirely.
+    ///
+    /// Their decoded data is removed, and their raw data is zeroed.
+    #[serde(default)]
+    pub packet_ids: Vec<u16>,
+
+    /// The paths of decoded fields to redact, separated by dots.
+    ///
+    /// These fields are removed from every packet.\
+    /// If a field is text, it is also zeroed in the packet's raw data.
+    #[serde(default)]
+    pub fields: Vec<String>,
+}
@@ -1,7 +1,12 @@
+use crate::app::game::GameManager;
 use crate::config::Config;
 use crate::system;
+use crate::utils::{MaybeError, Recover};
 use anyhow::Result;
+use serde::Serialize;
 use sqlx::{Pool, Sqlite, SqlitePool};
+use std::collections::BTreeMap;
+use std::fs;
 use std::fs::File;
 use std::sync::RwLock;
 
@@ -18,20 +23,117 @@ pub async fn initialize(config: &Config) -> Result<()> {
         let _ = File::create(&db_file)?;
     }
 
+    initialize_with(&format!("sqlite:{}", db_file.to_string_lossy())).await
+}
+
+/// Initializes the SQLite database pool from a database URL.
+///
+/// This is useful for connecting to a different database file, or `sqlite::memory:`.
+pub async fn initialize_with(url: &str) -> Result<()> {
     // Create the database connection pool.
-    let pool = SqlitePool::connect(&format!("sqlite:{}", db_file.to_string_lossy())).await?;
+    let pool = SqlitePool::connect(url).await?;
 
     // Run migrations to initialize the database.
     sqlx::migrate!("./migrations").run(&pool).await?;
 
     // Write the pool to the lock.
-    *POOL.write().unwrap() = Some(pool);
+    *POOL.write().recover() = Some(pool);
 
     Ok(())
 }
 
+/// Closes the SQLite database pool, if it was initialized.
+///
+/// After this, the pool needs to be initialized again before it can be used.
+pub async fn close() {
+    let pool = POOL.write().recover().take();
+    if let Some(pool) = pool {
+        pool.close().await;
+    }
+}
+
 /// Retrieves a reference to the SQLite database pool.
 pub fn get_pool() -> Pool<Sqlite> {
-    let pool = POOL.read().unwrap();
+    let pool = POOL.read().recover();
     pool.clone().expect("database pool should be initialized")
 }
+
+/// The tables created by the migrations.
+const TABLES: [&str; 4] = ["versions", "tools", "mods", "profiles"];
+
+/// Statistics about the database.
+#[derive(Serialize, Debug)]
+pub struct DatabaseStats {
+    /// The size of the database file, in bytes.
+    pub file_size: u64,
+
+    /// The number of rows in each table.
+    pub tables: BTreeMap<String, i64>,
+}
+
+/// Collects statistics about the database.
+pub async fn stats() -> Result<DatabaseStats> {
+    let db_file = system::resolve_path(&Config::get().data_file)?;
+    let pool = get_pool();
+
+    // Count the rows in each table.
+    let mut tables = BTreeMap::new();
+    for table in TABLES {
+        let rows: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM `{}`", table))
+            .fetch_one(&pool)
+            .await?;
+        tables.insert(table.to_string(), rows);
+    }
+
+    Ok(DatabaseStats {
+        file_size: fs::metadata(&db_file)?.len(),
+        tables,
+    })
+}
+
+/// The result of optimizing the database.
+#[derive(Serialize, Debug)]
+pub struct OptimizeResult {
+    /// The size of the database file before optimizing, in bytes.
+    pub size_before: u64,
+
+    /// The size of the database file after optimizing, in bytes.
+    pub size_after: u64,
+}
+
+/// Vacuums and optimizes the database.
+///
+/// All writes go through the game manager, so it is locked while this runs.
+pub async fn optimize() -> Result<OptimizeResult> {
+    let db_file = system::resolve_path(&Config::get().data_file)?;
+    let pool = get_pool();
+
+    // Pause any database writes.
+    let _game_manager = GameManager::get().write().await;
+
+    let size_before = fs::metadata(&db_file)?.len();
+
+    sqlx::query("VACUUM").execute(&pool).await?;
+    sqlx::query("PRAGMA optimize").execute(&pool).await?;
+
+    let size_after = fs::metadata(&db_file)?.len();
+
+    Ok(OptimizeResult {
+        size_before,
+        size_after,
+    })
+}
+
+/// Vacuums and optimizes the database.
+///
+/// This returns the size of the database file before and after.
+#[tauri::command]
+pub async fn database__optimize() -> MaybeError<OptimizeResult> {
+    match optimize().await {
+        Ok(result) => Ok(result),
+        Err(error) => {
+            warn!("Failed to optimize the database: {}", error);
+            Err("database.optimize-failed")
+        }
+    }
+}
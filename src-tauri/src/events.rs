@@ -1,35 +1,244 @@
 use log::warn;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{LazyLock, Mutex, OnceLock};
 use tauri::{AppHandle, Emitter};
+use crate::app::appearance::Preset;
 use crate::capabilities::sniffer::VisualPacket;
+use crate::utils;
+use crate::utils::Recover;
+
+/// The app handle used for emitting events from anywhere.
+///
+/// This is only set when running the desktop application.
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// The maximum number of recent events kept.
+const MAX_RECENT_EVENTS: usize = 100;
+
+/// The most recently emitted events, oldest first.
+static RECENT_EVENTS: Mutex<VecDeque<RecordedEvent>> = Mutex::new(VecDeque::new());
+
+/// The number of listeners subscribed to each event.
+static SUBSCRIPTIONS: LazyLock<Mutex<HashMap<&'static str, usize>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
 
 pub enum Event {
+    AppReady,
     LanguageChanged(String),
-    VisualizerPacket(VisualPacket)
+    VisualizerPacket(VisualPacket),
+    SnifferStatusChanged(bool),
+    SnifferError(String),
+    GameStatusChanged(bool),
+    GameLaunchProgress(String),
+    GameWindowFound(u64),
+    GameExited {
+        code: Option<i32>,
+        crashed: bool
+    },
+    ProfileChanged(String),
+    DownloadProgress {
+        file: String,
+        received: u64,
+        total: Option<u64>
+    },
+    AppearanceChanged(Preset),
+    Log {
+        level: String,
+        target: String,
+        message: String
+    },
+    ModDiscovered {
+        path: String
+    }
 }
 
 impl Event {
     /// Converts the enum into an event string used on the frontend.
     fn to_string(&self) -> &'static str {
         match self {
+            Event::AppReady => "ysc://app/ready",
             Event::LanguageChanged(_) => "ysc://language/changed",
-            Event::VisualizerPacket(_) => "ysc://visualizer/packet"
+            Event::VisualizerPacket(_) => "ysc://visualizer/packet",
+            Event::SnifferStatusChanged(_) => "ysc://sniffer/status",
+            Event::SnifferError(_) => "ysc://sniffer/error",
+            Event::GameStatusChanged(_) => "ysc://game/status",
+            Event::GameLaunchProgress(_) => "ysc://game/launch/progress",
+            Event::GameWindowFound(_) => "ysc://game/window",
+            Event::GameExited { .. } => "ysc://game/exited",
+            Event::ProfileChanged(_) => "ysc://profile/changed",
+            Event::DownloadProgress { .. } => "ysc://download/progress",
+            Event::AppearanceChanged(_) => "ysc://appearance/changed",
+            Event::Log { .. } => "ysc://log",
+            Event::ModDiscovered { .. } => "ysc://mods/discovered"
         }
     }
 
+    /// Converts the enum into the payload sent to the frontend.
+    fn payload(&self) -> Value {
+        match self {
+            Event::AppReady => Value::Null,
+            Event::LanguageChanged(language) => json!(language),
+            Event::VisualizerPacket(packet) => json!(packet),
+            Event::SnifferStatusChanged(running) => json!(running),
+            Event::SnifferError(error) => json!(error),
+            Event::GameStatusChanged(open) => json!(open),
+            Event::GameLaunchProgress(step) => json!(step),
+            Event::GameWindowFound(window) => json!(window),
+            Event::GameExited { code, crashed } => json!({ "code": code, "crashed": crashed }),
+            Event::ProfileChanged(profile_id) => json!(profile_id),
+            Event::DownloadProgress { file, received, total } => {
+                json!({ "file": file, "received": received, "total": total })
+            }
+            Event::AppearanceChanged(preset) => json!(preset),
+            Event::Log { level, target, message } => {
+                json!({ "level": level, "target": target, "message": message })
+            }
+            Event::ModDiscovered { path } => json!({ "path": path })
+        }
+    }
+
+    /// Checks if this event is kept in the recent events.
+    ///
+    /// Frequent events, such as packets and log messages, are not kept.
+    fn is_recorded(&self) -> bool {
+        !matches!(
+            self,
+            Event::VisualizerPacket(_) | Event::Log { .. } | Event::DownloadProgress { .. }
+        )
+    }
+
+    /// Checks if any listener is subscribed to this event.
+    ///
+    /// Expensive events should only be emitted if this is `true`.
+    pub fn has_subscribers(&self) -> bool {
+        SUBSCRIPTIONS
+            .lock()
+            .recover()
+            .get(self.to_string())
+            .is_some_and(|count| *count > 0)
+    }
+
     /// Emits this event to the global app handle.
     pub fn send(&self, app_handle: &AppHandle) {
-        if let Err(error) = match self {
-            Event::LanguageChanged(language) => app_handle.emit(self.to_string(), language.to_string()),
-            Event::VisualizerPacket(packet) => app_handle.emit(self.to_string(), packet.clone())
-        } {
+        let payload = self.payload();
+        if self.is_recorded() {
+            record(self.to_string(), payload.clone());
+        }
+
+        if let Err(error) = app_handle.emit(self.to_string(), payload) {
             warn!("{} {}", t!("backend.tauri.emit.error"), error);
         }
     }
 }
 
+/// An event which was emitted to the frontend.
+#[derive(Serialize, Debug, Clone)]
+pub struct RecordedEvent {
+    /// The name of the event. (e.g. `ysc://profile/changed`)
+    pub event: &'static str,
+
+    /// The payload the event was emitted with.
+    pub payload: Value,
+
+    /// When the event was emitted, as a UNIX timestamp in milliseconds.
+    pub timestamp: u64
+}
+
+/// Keeps the event in the recent events.
+///
+/// The oldest event is removed once there are too many.
+fn record(event: &'static str, payload: Value) {
+    let mut recent = RECENT_EVENTS.lock().recover();
+    if recent.len() >= MAX_RECENT_EVENTS {
+        recent.pop_front();
+    }
+
+    recent.push_back(RecordedEvent {
+        event,
+        payload,
+        timestamp: utils::unix_timestamp_millis()
+    });
+}
+
+/// Returns the most recently emitted events, oldest first.
+///
+/// This lets windows catch up on events emitted before they were opened.\
+/// If no limit is given, all kept events are returned.
+#[tauri::command]
+pub fn events__recent(limit: Option<usize>) -> Vec<RecordedEvent> {
+    let recent = RECENT_EVENTS.lock().recover();
+    let skip = limit.map_or(0, |limit| recent.len().saturating_sub(limit));
+
+    recent.iter().skip(skip).cloned().collect()
+}
+
+/// Subscribes a listener to an event.
+///
+/// This should be called when a window starts listening to an expensive event.
+#[tauri::command]
+pub fn events__subscribe(event: String) {
+    let Some(event) = subscribable(&event) else {
+        warn!("Unable to subscribe to unknown event '{}'.", event);
+        return;
+    };
+
+    *SUBSCRIPTIONS.lock().recover().entry(event).or_default() += 1;
+}
+
+/// Unsubscribes a listener from an event.
+///
+/// This should be called when a window stops listening to an event it subscribed to.
+#[tauri::command]
+pub fn events__unsubscribe(event: String) {
+    let Some(event) = subscribable(&event) else {
+        return;
+    };
+
+    if let Some(count) = SUBSCRIPTIONS.lock().recover().get_mut(event) {
+        *count = count.saturating_sub(1);
+    }
+}
+
+/// Returns the name of an event which listeners can subscribe to.
+///
+/// Only events which are skipped without subscribers can be subscribed to.
+fn subscribable(event: &str) -> Option<&'static str> {
+    ["ysc://visualizer/packet"]
+        .into_iter()
+        .find(|name| *name == event)
+}
+
+/// Stores the app handle for emitting global events.
+///
+/// This should only be called once, when the desktop application is set up.
+pub fn set_app_handle(app_handle: &AppHandle) {
+    if APP_HANDLE.set(app_handle.clone()).is_err() {
+        warn!("The global app handle was already set.");
+    }
+}
+
+/// Returns the global app handle.
+///
+/// If the desktop application isn't running, this returns `None`.
+pub fn app_handle<'a>() -> Option<&'a AppHandle> {
+    APP_HANDLE.get()
+}
+
 /// Emits a global event to the window.
 ///
 /// Requires using the event enum.
 pub fn emit_event(app_handle: &AppHandle, event: Event) {
     event.send(app_handle);
 }
+
+/// Emits a global event without an app handle.
+///
+/// This can be used from background threads.\
+/// If the desktop application isn't running, this does nothing.
+pub fn emit_global(event: Event) {
+    if let Some(app_handle) = APP_HANDLE.get() {
+        event.send(app_handle);
+    }
+}
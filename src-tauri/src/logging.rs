@@ -0,0 +1,119 @@
+use crate::events;
+use crate::events::Event;
+use crate::utils::{MaybeError, Recover};
+use log::{LevelFilter, Record};
+use std::cell::Cell;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri_plugin_log::{fern, Target, TargetKind};
+
+/// The maximum number of log records forwarded to the frontend each second.
+///
+/// Records past this limit are dropped.
+const MAX_RECORDS_PER_SECOND: u32 = 50;
+
+/// The most verbose level of log records forwarded to the frontend.
+static FORWARD_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Info as usize);
+
+/// The rate limit for forwarding log records.
+static RATE_LIMIT: LazyLock<Mutex<RateLimit>> = LazyLock::new(|| {
+    Mutex::new(RateLimit {
+        window_start: Instant::now(),
+        forwarded: 0,
+        dropped: 0,
+    })
+});
+
+thread_local! {
+    /// Whether this thread is currently forwarding a log record.
+    ///
+    /// This prevents records logged while emitting from being forwarded again.
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Counts the log records forwarded in the current one-second window.
+struct RateLimit {
+    window_start: Instant,
+    forwarded: u32,
+    dropped: u32,
+}
+
+/// Creates a log target which forwards log records to the frontend.
+///
+/// Records are emitted as `ysc://log` events.
+pub fn event_target() -> Target {
+    let dispatch = fern::Dispatch::new().chain(fern::Output::call(forward));
+    Target::new(TargetKind::Dispatch(dispatch))
+}
+
+/// Forwards a log record to the frontend.
+fn forward(record: &Record) {
+    // Check if the record is verbose enough to forward.
+    if record.level() as usize > FORWARD_LEVEL.load(Ordering::Relaxed) {
+        return;
+    }
+
+    // Don't forward records logged while forwarding.
+    if FORWARDING.replace(true) {
+        return;
+    }
+
+    if let Some(dropped) = allow_record() {
+        if dropped > 0 {
+            events::emit_global(Event::Log {
+                level: "WARN".to_string(),
+                target: module_path!().to_string(),
+                message: format!("{} log messages were not shown.", dropped),
+            });
+        }
+
+        events::emit_global(Event::Log {
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        });
+    }
+
+    FORWARDING.set(false);
+}
+
+/// Checks if another record can be forwarded in the current window.
+///
+/// If it can, this returns the number of records dropped in the previous window.
+fn allow_record() -> Option<u32> {
+    let mut rate_limit = RATE_LIMIT.lock().recover();
+
+    // Start a new window every second.
+    let mut dropped = 0;
+    if rate_limit.window_start.elapsed() >= Duration::from_secs(1) {
+        dropped = rate_limit.dropped;
+
+        rate_limit.window_start = Instant::now();
+        rate_limit.forwarded = 0;
+        rate_limit.dropped = 0;
+    }
+
+    if rate_limit.forwarded >= MAX_RECORDS_PER_SECOND {
+        rate_limit.dropped += 1;
+        return None;
+    }
+
+    rate_limit.forwarded += 1;
+    Some(dropped)
+}
+
+/// Sets the most verbose level of log records forwarded to the frontend.
+///
+/// The level is one of `off`, `error`, `warn`, `info`, `debug`, or `trace`.
+#[tauri::command]
+pub fn log__set_level(level: String) -> MaybeError<()> {
+    let Ok(level) = LevelFilter::from_str(&level) else {
+        return Err("backend.log.error.bad-level");
+    };
+
+    FORWARD_LEVEL.store(level as usize, Ordering::Relaxed);
+
+    Ok(())
+}
@@ -10,15 +10,17 @@ extern crate dotenv_codegen;
 #[macro_use]
 extern crate rust_i18n;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{arg, Command};
 use game::GameManager;
 use lazy_static::lazy_static;
 use std::collections::HashMap;
 use std::fs;
+use std::path::PathBuf;
 use std::sync::RwLock;
+use std::time::{Duration, Instant};
 use log::LevelFilter;
-use tauri::{generate_handler, AppHandle, Manager};
+use tauri::{generate_handler, AppHandle, Manager, RunEvent};
 use tauri_plugin_log::{Target, TargetKind, TimezoneStrategy};
 use tokio::runtime::Handle;
 use tokio::sync::RwLockReadGuard;
@@ -29,13 +31,14 @@ mod cli;
 mod config;
 mod database;
 mod events;
+mod logging;
 mod state;
 mod system;
 mod utils;
 mod window;
 
-use crate::app::{appearance, game, profile};
-use crate::capabilities::sniffer;
+use crate::app::{appearance, diagnostics, game, mods, profile, runners};
+use crate::capabilities::{analysis, memory, seeds, sniffer};
 use crate::config::{Config, Language};
 use crate::state::*;
 
@@ -66,38 +69,95 @@ async fn setup_app() -> Result<()> {
 
     // If the launcher should elevate, do so now.
     if config.launcher.always_elevate && !system::is_elevated() {
-        system::elevate()?;
+        system::elevate().context("Failed to elevate the launcher")?;
     }
 
-    // Check if the data directory exists.
+    // Create the data directories.
+    // Each one is checked, in case a previous setup only created some of them.
     let app_data_dir = utils::app_data_dir()?;
-    if !app_data_dir.exists() {
-        fs::create_dir_all(&app_data_dir)?;
-        fs::create_dir(app_data_dir.join("mods"))?;
-        fs::create_dir(app_data_dir.join("cache"))?;
-        fs::create_dir(app_data_dir.join("dumps"))?;
-        fs::create_dir(app_data_dir.join("images"))?;
-        fs::create_dir(app_data_dir.join("sniffer"))?;
+    for directory in ["mods", "cache", "dumps", "images", "sniffer"] {
+        fs::create_dir_all(app_data_dir.join(directory))
+            .with_context(|| format!("Failed to create the '{directory}' data directory"))?;
+    }
+
+    // Prune old packet dumps, if configured.
+    if let Some(retention) = &config.launcher.dump_retention {
+        if let Err(error) = sniffer::prune_dumps(retention.max_age_days, retention.max_count) {
+            warn!("Failed to prune packet dumps: {}", error);
+        }
     }
 
     // Set the language.
     rust_i18n::set_locale(&config.language);
 
     // Create the database connection pool.
-    database::initialize(&config).await?;
+    database::initialize(&config)
+        .await
+        .context("Failed to open the database")?;
 
     // Load data.
     let mut game_manager = GameManager::get().write().await;
-    game_manager.load_all().await?;
+    game_manager
+        .load_all()
+        .await
+        .context("Failed to load data from the database")?;
 
     Ok(())
 }
 
+/// Cleans up any state left behind by a failed `setup_app`.
+///
+/// This drops partially loaded data and closes the database pool.
+async fn cleanup_setup() {
+    *GameManager::get().write().await = GameManager::default();
+    database::close().await;
+}
+
+/// Reads a path flag from the command line arguments.
+///
+/// This is used for flags which are needed before the arguments can be parsed.\
+/// Both `--flag <path>` and `--flag=<path>` are accepted.
+fn path_flag(flag: &str) -> Option<PathBuf> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.strip_prefix(&format!("{flag}=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    None
+}
+
 /// The syntax tree for the command line interface.
 fn clap() -> Command {
-    Command::new("ysc")
+    let command = Command::new("ysc")
         .about(t_str!("cli.about"))
-        .subcommand(Command::new("sniff").about(t_str!("cli.sniff")))
+        .version(env!("CARGO_PKG_VERSION"))
+        .arg(
+            arg!(--config <PATH>)
+                .help(t_str!("cli.config"))
+                .global(true),
+        )
+        .arg(
+            arg!(--"data-dir" <PATH>)
+                .help(t_str!("cli.data-dir"))
+                .global(true),
+        )
+        .arg(arg!(--portable).help(t_str!("cli.portable")).global(true))
+        .subcommand(Command::new("version").about(t_str!("cli.version")))
+        .subcommand(
+            Command::new("sniff")
+                .about(t_str!("cli.sniff"))
+                .arg(arg!(--filter <BPF>).help(t_str!("cli.sniff.filter")))
+                .arg(
+                    arg!(--"max-packets" <N>)
+                        .help(t_str!("cli.sniff.max-packets"))
+                        .value_parser(clap::value_parser!(usize)),
+                ),
+        )
         .subcommand(
             Command::new("game")
                 .about(t_str!("cli.game"))
@@ -130,13 +190,66 @@ fn clap() -> Command {
                         .arg(arg!(--profile <NAME>)),
                 ),
         )
+        .subcommand(
+            Command::new("db")
+                .about(t_str!("cli.db"))
+                .arg_required_else_help(true)
+                .subcommand_required(true)
+                .arg(arg!(--json).help(t_str!("cli.db.json")).global(true))
+                .subcommand(Command::new("stats").about(t_str!("cli.db.stats")))
+                .subcommand(Command::new("profiles").about(t_str!("cli.db.profiles")))
+                .subcommand(Command::new("versions").about(t_str!("cli.db.versions")))
+                .subcommand(Command::new("tools").about(t_str!("cli.db.tools")))
+                .subcommand(Command::new("mods").about(t_str!("cli.db.mods")))
+                .subcommand(
+                    Command::new("optimize")
+                        .about(t_str!("cli.db.optimize"))
+                        .visible_alias("vacuum"),
+                ),
+        );
+
+    // The packet injector is only available if it was compiled in.
+    #[cfg(feature = "packet-injector")]
+    let command = command.subcommand(Command::new("inject").about(t_str!("cli.inject")));
+
+    command
 }
 
 #[tokio::main]
 async fn main() {
+    // Record when the launcher started, for the splash screen.
+    let started = Instant::now();
+
+    // The runtime created by `tokio::main` owns every task, in both the CLI and the desktop app.
+    // Tauri is given a handle to it, so `tauri::async_runtime` never creates a second runtime.
+    tauri::async_runtime::set(Handle::current());
+
+    // Use a different data directory and configuration file, if they were given.
+    // This has to happen before the configuration is loaded, so before the arguments are parsed.
+    // A portable install keeps its data beside the executable instead.
+    let portable = std::env::args().any(|arg| arg == "--portable") || utils::is_portable();
+    let data_dir = match path_flag("--data-dir") {
+        Some(path) => Some(Ok(path)),
+        None if portable => Some(utils::portable_data_dir()),
+        None => None,
+    };
+    if let Some(path) = data_dir {
+        if let Err(error) = path.and_then(utils::set_app_data_dir) {
+            eprintln!("Failed to use the data directory: {:#?}", error);
+            return;
+        }
+    }
+    if let Some(path) = path_flag("--config") {
+        if let Err(error) = config::set_config_path(path) {
+            eprintln!("Failed to use the configuration file: {:#?}", error);
+            return;
+        }
+    }
+
     // Run the application setup function.
     if let Err(error) = setup_app().await {
         eprintln!("Failed to setup application: {:#?}", error);
+        cleanup_setup().await;
         return;
     }
 
@@ -162,11 +275,8 @@ async fn main() {
     let matches = matches.subcommand();
 
     if matches.is_none() {
-        // Set the Tauri async runtime.
-        tauri::async_runtime::set(Handle::current());
-
         // Run the desktop app if no sub-command was provided.
-        run_tauri_app().await;
+        run_tauri_app(started).await;
         return;
     }
 
@@ -205,24 +315,64 @@ fn translate(key: String, args: Option<HashMap<String, String>>) -> String {
 fn setup_tauri_app(
     app_handle: &AppHandle,
     game_manager: RwLockReadGuard<'_, GameManager>,
+    started: Instant,
 ) -> Result<()> {
+    // Store the app handle for background threads.
+    events::set_app_handle(app_handle);
+
     // Initialize global state.
     app_handle.manage(SelectedProfile::new(game_manager));
 
+    // Watch the mods directory, if enabled.
+    if Config::launcher().watch_mods {
+        if let Err(error) = mods::start_watcher() {
+            warn!("Failed to watch the mods directory: {}", error);
+        }
+    }
+
+    // Show the splash screen until the launcher is ready.
+    let splash = Config::launcher().splash;
+    if splash.enabled {
+        if let Err(error) = window::open_splash(app_handle) {
+            warn!("Failed to open the splash screen: {}", error);
+        }
+    }
+
+    let min_duration = if splash.enabled {
+        Duration::from_millis(splash.min_duration)
+    } else {
+        Duration::ZERO
+    };
+    tauri::async_runtime::spawn(window::finish_startup(
+        app_handle.clone(),
+        started,
+        min_duration,
+    ));
+
     Ok(())
 }
 
 /// Runs the Tauri desktop application.
 // noinspection RsUnnecessaryQualifications
-async fn run_tauri_app() {
+async fn run_tauri_app(started: Instant) {
     let game_manager = GameManager::get().read().await;
 
+    // Keep the logs in the data directory when it was overridden, so it holds everything.
+    let log_dir = match utils::app_data_dir() {
+        Ok(path) if utils::has_custom_data_dir() => TargetKind::Folder {
+            path: path.join("logs"),
+            file_name: None,
+        },
+        _ => TargetKind::LogDir { file_name: None },
+    };
+
     tauri::Builder::default()
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
                     Target::new(TargetKind::Stdout),
-                    Target::new(TargetKind::LogDir { file_name: None }),
+                    Target::new(log_dir),
+                    logging::event_target(),
                 ])
                 .level(LevelFilter::Debug)
                 .format(|consumer, message, record| {
@@ -242,24 +392,104 @@ async fn run_tauri_app() {
         .invoke_handler(generate_handler![
             translate,
             app::set_language,
+            app::i18n__audit,
+            app::app__can_close,
+            app::app__info,
+            app::app__data_dir,
+            app::app__is_first_run,
+            app::app__complete_onboarding,
+            app::file__hash,
+            logging::log__set_level,
             game::game__is_open,
             game::game__launch,
+            game::game__launch_vanilla,
+            game::game__simulate_launch,
+            game::game__command_line,
+            game::game__safe_mode_suggestion,
+            game::game__inject,
+            game::game__eject,
+            game::game__modules,
+            game::game__suspend,
+            game::game__resume,
+            game::game__wait_for_window,
+            game::game__update_tool_hash,
+            game::game__update_mod_hash,
             game::game__locate,
+            game::game__locate_interactive,
+            game::game__autodetect,
+            game::version__relocate,
             profile::profile__get_all,
+            profile::profile__get_selected,
             profile::profile__new_profile,
             profile::profile__set_profile,
+            profile::profile__export,
+            profile::profile__import,
+            profile::profile__set_wine_prefix,
+            profile::profile__set_dll_overrides,
+            profile::profile__get_launch_args,
+            profile::profile__set_launch_args,
+            profile::profile__resolved_paths,
             sniffer::sniffer__load,
+            sniffer::sniffer__latest_dump,
+            sniffer::dumps__prune,
+            sniffer::sniffer__clear_buffer,
+            sniffer::sniffer__export_packet,
+            sniffer::sniffer__set_note,
+            sniffer::sniffer__get_notes,
+            sniffer::sniffer__save_dump,
+            sniffer::sniffer__upgrade_dump,
+            sniffer::sniffer__export_redacted,
+            sniffer::sniffer__add_marker,
+            sniffer::sniffer__get_markers,
+            analysis::sniffer__diff,
+            analysis::sniffer__find_bytes,
+            analysis::sniffer__find_field,
+            seeds::seeds__validate,
+            seeds::seeds__repair,
+            seeds::seeds__diagnose,
+            memory::memory__read,
             app::sniffer::sniffer__run,
+            sniffer::sniffer__check,
+            sniffer::sniffer__stats,
             app::sniffer::sniffer__open,
+            app::sniffer::sniffer__close_visualizer,
+            app::sniffer::sniffer__set_device,
             config::config__get,
+            events::events__recent,
+            events::events__subscribe,
+            events::events__unsubscribe,
+            database::database__optimize,
             window::window__close,
+            window::window__minimize,
+            window::window__toggle_maximize,
+            window::window__start_drag,
             appearance::appearance__background,
-            appearance::appearance__default_splash
+            appearance::appearance__default_splash,
+            appearance::appearance__game_info,
+            appearance::appearance__list_presets,
+            appearance::appearance__set_preset,
+            diagnostics::diagnostics__run,
+            diagnostics::diagnostics__install_pcap,
+            mods::mods__watch_start,
+            mods::mods__watch_stop,
+            runners::launcher__detect_runners,
+            runners::wine__list_prefixes,
+            runners::wine__create_prefix
         ])
         .setup(|app| {
-            setup_tauri_app(app.handle(), game_manager)?;
+            setup_tauri_app(app.handle(), game_manager, started)?;
             Ok(())
         })
-        .run(utils::build_context())
-        .expect("error while running tauri application");
+        .build(utils::build_context())
+        .expect("error while running tauri application")
+        .run(|_, event| {
+            // Stop the sniffer if the application is exiting.
+            // The process ends after this, so wait for the sniffer to close the capture.
+            if let RunEvent::Exit = event {
+                tokio::task::block_in_place(|| {
+                    Handle::current().block_on(app::sniffer::shutdown_sniffer())
+                });
+                mods::stop_watcher();
+            }
+        });
 }
@@ -1,15 +1,52 @@
 use crate::app::game::{GameManager, Profile};
-use crate::{utils, GLOBAL_STATE};
+use crate::events::Event;
+use crate::utils::Recover;
+use crate::{events, utils, GLOBAL_STATE};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::sync::Mutex;
+use tauri::Manager;
 use tokio::sync::RwLockReadGuard;
 
+/// The current version of the state format.
+///
+/// State saved without a version is version 1.
+const STATE_VERSION: u32 = 2;
+
 /// This state can be saved to the disk.
-#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PersistentState {
+    /// The version of the state format.
+    #[serde(default)]
+    pub version: u32,
+
     /// This is the ID of the user's selected profile.
     pub selected_profile: Option<String>,
+
+    /// Whether the user has yet to complete the onboarding.
+    ///
+    /// This is `true` on the first run, until `app__complete_onboarding` is called.\
+    /// State saved before this existed belongs to existing users, so it defaults to `false`.
+    #[serde(default)]
+    pub first_run: bool,
+
+    /// The number of consecutive times the game crashed shortly after launching.
+    ///
+    /// This is reset once the game exits cleanly.
+    #[serde(default)]
+    pub crash_count: u32,
+}
+
+impl Default for PersistentState {
+    fn default() -> Self {
+        PersistentState {
+            version: STATE_VERSION,
+            selected_profile: None,
+            first_run: true,
+            crash_count: 0,
+        }
+    }
 }
 
 impl PersistentState {
@@ -19,7 +56,7 @@ impl PersistentState {
         if let Ok(app_data_dir) = utils::app_data_dir() {
             let state_path = app_data_dir.join("state.json");
             if let Ok(state_file) = std::fs::read_to_string(&state_path) {
-                return serde_json::from_str(&state_file).unwrap_or_default();
+                return PersistentState::parse(&state_file);
             }
         }
 
@@ -27,6 +64,33 @@ impl PersistentState {
         PersistentState::default()
     }
 
+    /// Parses saved state, upgrading it to the current format.
+    ///
+    /// Invalid state is replaced with the default state.
+    fn parse(data: &str) -> Self {
+        serde_json::from_str(data)
+            .map(PersistentState::upgrade)
+            .and_then(serde_json::from_value)
+            .unwrap_or_default()
+    }
+
+    /// Upgrades the JSON data of saved state to the current format.
+    ///
+    /// Version 1 state was saved before the onboarding existed, so it belongs to existing users.
+    fn upgrade(mut state: Value) -> Value {
+        let Some(fields) = state.as_object_mut() else {
+            return state;
+        };
+
+        let version = fields.get("version").and_then(Value::as_u64).unwrap_or(1);
+        if version < 2 {
+            fields.entry("first_run").or_insert(Value::Bool(false));
+        }
+
+        fields.insert("version".to_string(), Value::from(STATE_VERSION));
+        state
+    }
+
     /// Saves the state to the disk.
     pub fn save(&self) -> Result<()> {
         // Get the application data directory.
@@ -48,7 +112,7 @@ impl SelectedProfile {
     /// Creates a new instance of the selected profile state.
     pub fn new(game_manager: RwLockReadGuard<'_, GameManager>) -> Self {
         // Try getting the profile from the global state.
-        let state = GLOBAL_STATE.read().unwrap();
+        let state = GLOBAL_STATE.read().recover();
         if let Some(state) = &state.selected_profile {
             if let Some(profile) = game_manager.get_profile(state) {
                 return Self(Mutex::new(Some(profile)));
@@ -58,3 +122,72 @@ impl SelectedProfile {
         Self(Mutex::new(None))
     }
 }
+
+/// Sets the user's selected profile.
+///
+/// This is the only way the selected profile should be changed.\
+/// It updates the persistent state and, if the desktop application is running, the `SelectedProfile` state.
+pub fn select_profile(profile: Profile) {
+    // Set the persisted state's selected profile.
+    let mut state = GLOBAL_STATE.write().recover();
+    state.selected_profile = Some(profile.id.clone());
+
+    if let Err(error) = state.save() {
+        warn!("Failed to save the persistent state: {}", error);
+    }
+
+    drop(state);
+
+    // Set the app instance's selected profile.
+    let profile_id = profile.id.clone();
+    if let Some(app_handle) = events::app_handle() {
+        if let Some(selected_profile) = app_handle.try_state::<SelectedProfile>() {
+            *selected_profile.0.lock().recover() = Some(profile);
+        }
+    }
+
+    // Notify all windows of the change.
+    events::emit_global(Event::ProfileChanged(profile_id));
+}
+
+/// Updates the selected profile, if it is the given profile.
+///
+/// This should be called after a profile is changed, so the selection isn't stale.
+pub fn refresh_selected(profile: Profile) {
+    let selected = GLOBAL_STATE.read().recover().selected_profile.clone();
+    if selected.as_ref() == Some(&profile.id) {
+        select_profile(profile);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_state_is_upgraded() {
+        let state = PersistentState::parse(r#"{ "selected_profile": "profile" }"#);
+
+        assert_eq!(state.version, STATE_VERSION);
+        assert_eq!(state.selected_profile.as_deref(), Some("profile"));
+
+        // Unversioned state belongs to existing users, who already finished the onboarding.
+        assert!(!state.first_run);
+    }
+
+    #[test]
+    fn current_state_is_kept() {
+        let state = PersistentState::parse(r#"{ "version": 2, "first_run": true }"#);
+
+        assert_eq!(state.version, STATE_VERSION);
+        assert!(state.first_run);
+    }
+
+    #[test]
+    fn invalid_state_is_replaced() {
+        let state = PersistentState::parse("not json");
+
+        assert_eq!(state.version, STATE_VERSION);
+        assert!(state.first_run);
+    }
+}
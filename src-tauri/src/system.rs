@@ -1,5 +1,5 @@
 use crate::utils;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use std::path::PathBuf;
 use sysinfo::System;
 
@@ -128,6 +128,29 @@ pub fn resolve_path<S: AsRef<str>>(path: S) -> Result<PathBuf> {
     ))
 }
 
+/// Resolves a path to an absolute path, like `resolve_path`.
+///
+/// The resolved path must exist and be inside one of the allowed roots.\
+/// Symbolic links and `..` components are resolved before this is checked.
+pub fn resolve_path_within<S: AsRef<str>>(path: S, roots: &[PathBuf]) -> Result<PathBuf> {
+    let path = resolve_path(path)?.canonicalize()?;
+
+    // Check if the path is inside any of the roots.
+    let allowed = roots
+        .iter()
+        .filter_map(|root| root.canonicalize().ok())
+        .any(|root| path.starts_with(root));
+
+    if !allowed {
+        return Err(anyhow!(
+            "'{}' is outside of the allowed roots",
+            path.display()
+        ));
+    }
+
+    Ok(path)
+}
+
 /// The result of opening the file.
 ///
 /// This is used in `system::open_executable`.
@@ -179,12 +202,46 @@ pub fn open_executable<S: AsRef<str>>(path: S, args: Option<String>) -> Result<O
 
 /// Checks if the process is running.
 pub fn find_process<S: AsRef<str>>(process_name: S) -> bool {
+    find_process_id(process_name).is_some()
+}
+
+/// Finds the ID of a running process by its name.
+///
+/// If multiple processes have the name, the first one found is returned.
+pub fn find_process_id<S: AsRef<str>>(process_name: S) -> Option<u32> {
     let mut system = System::new();
     system.refresh_all();
 
     let process_name = process_name.as_ref().as_ref();
     let mut processes = system.processes_by_exact_name(process_name);
-    processes.next().is_some()
+    processes.next().map(|process| process.pid().as_u32())
+}
+
+/// The machine type of 64-bit x86 executables.
+pub const MACHINE_AMD64: u16 = 0x8664;
+
+/// Reads the machine type from a portable executable's (`.exe`/`.dll`) headers.
+///
+/// This can be used to check the architecture of a DLL before injecting it.
+pub fn executable_machine(path: &PathBuf) -> Result<u16> {
+    let data = std::fs::read(path)?;
+
+    // Check for the DOS header's signature.
+    if !data.starts_with(b"MZ") {
+        return Err(anyhow!("missing DOS header"));
+    }
+
+    // The offset of the PE header is stored at 0x3C.
+    let offset = data
+        .get(0x3C..0x40)
+        .map(|bytes| u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize)
+        .ok_or(anyhow!("truncated DOS header"))?;
+
+    // The machine type follows the PE signature.
+    match data.get(offset..offset + 6) {
+        Some([b'P', b'E', 0, 0, low, high]) => Ok(u16::from_le_bytes([*low, *high])),
+        _ => Err(anyhow!("missing PE header")),
+    }
 }
 
 /// Canonicalizes a path using the system's path rules.
@@ -218,3 +275,61 @@ impl AsCString for str {
         CString::new(self).unwrap()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Creates a directory with a `mods` root, and a file outside of it.
+    fn layout() -> (TempDir, PathBuf) {
+        let directory = tempfile::tempdir().unwrap();
+        let root = directory.path().join("mods");
+        fs::create_dir(&root).unwrap();
+        fs::write(root.join("inside.dll"), "").unwrap();
+        fs::write(directory.path().join("outside.dll"), "").unwrap();
+
+        (directory, root)
+    }
+
+    fn resolve(path: PathBuf, root: &Path) -> Result<PathBuf> {
+        resolve_path_within(path.to_string_lossy(), &[root.to_path_buf()])
+    }
+
+    #[test]
+    fn files_inside_the_root_are_allowed() {
+        let (_directory, root) = layout();
+
+        let resolved = resolve(root.join("inside.dll"), &root).unwrap();
+        assert_eq!(resolved, root.join("inside.dll").canonicalize().unwrap());
+    }
+
+    #[test]
+    fn parent_components_cant_leave_the_root() {
+        let (_directory, root) = layout();
+
+        assert!(resolve(root.join("..").join("outside.dll"), &root).is_err());
+    }
+
+    #[test]
+    fn sibling_directories_with_the_same_prefix_are_rejected() {
+        let (directory, root) = layout();
+        let sibling = directory.path().join("mods-evil");
+        fs::create_dir(&sibling).unwrap();
+        fs::write(sibling.join("tool.dll"), "").unwrap();
+
+        assert!(resolve(sibling.join("tool.dll"), &root).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symbolic_links_cant_leave_the_root() {
+        let (directory, root) = layout();
+        let link = root.join("link.dll");
+        std::os::unix::fs::symlink(directory.path().join("outside.dll"), &link).unwrap();
+
+        assert!(resolve(link, &root).is_err());
+    }
+}
@@ -1,10 +1,20 @@
 use anyhow::{anyhow, Result};
 use base64::prelude::BASE64_STANDARD;
 use base64::Engine;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use md5::Md5;
 use rand::distr::Alphanumeric;
 use rand::Rng;
+use reqwest::Client;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{Read, Write};
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::{LazyLock, LockResult, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use sys_locale::get_locale;
 use tauri::Context;
 use ys_sniffer::PacketSource;
@@ -14,11 +24,55 @@ use ys_sniffer::PacketSource;
 /// It might return an error message if something went wrong.
 pub type MaybeError<T> = std::result::Result<T, &'static str>;
 
+/// An extension for translating the error of a `MaybeError`.
+///
+/// Errors returned to the GUI are translated by the frontend.\
+/// The CLI should use this to translate them before displaying them.
+pub trait Translate<T> {
+    /// Translates the error into the current language.
+    fn translated(self) -> std::result::Result<T, String>;
+}
+
+impl<T> Translate<T> for MaybeError<T> {
+    fn translated(self) -> std::result::Result<T, String> {
+        self.map_err(|key| t!(key).to_string())
+    }
+}
+
+/// An extension for recovering the guard of a poisoned lock.
+///
+/// Locks are poisoned when a thread panics while holding them.\
+/// Most of the application's locks guard plain data, which is safe to read afterward.
+pub trait Recover<T> {
+    /// Returns the lock guard, recovering it if the lock was poisoned.
+    fn recover(self) -> T;
+}
+
+impl<T> Recover<T> for LockResult<T> {
+    fn recover(self) -> T {
+        self.unwrap_or_else(|poisoned| {
+            warn!("A lock was poisoned; recovering its value.");
+            poisoned.into_inner()
+        })
+    }
+}
+
 /// This is the identifier of the app.
 ///
 /// This is used in path resolving.
 static APP_IDENTIFIER: &str = "ys-compass";
 
+/// The HTTP client shared by all requests.
+static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .expect("failed to create the HTTP client")
+});
+
+/// The number of times a download is attempted before failing.
+const DOWNLOAD_ATTEMPTS: u64 = 3;
+
 /// Creates the context for the Tauri app.
 ///
 /// This method is isolated as to not slow down IDE performance in `main.rs`.
@@ -45,12 +99,12 @@ pub fn system_locale() -> String {
     get_locale().unwrap_or_else(|| String::from("en-us"))
 }
 
-/// Returns the current UNIX timestamp.
-pub fn unix_timestamp() -> u64 {
+/// Returns the current UNIX timestamp in milliseconds.
+pub fn unix_timestamp_millis() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("time went backwards")
-        .as_secs()
+        .as_millis() as u64
 }
 
 /// Writes the text content to the file at the given path.
@@ -58,20 +112,100 @@ pub fn write_file<S: AsRef<str>>(path: &PathBuf, data: S) -> Result<()> {
     std::fs::write(path, data.as_ref()).map_err(|e| anyhow!(e))
 }
 
+/// Writes the text content to the file at the given path, compressed with gzip.
+pub fn write_compressed_file<S: AsRef<str>>(path: &PathBuf, data: S) -> Result<()> {
+    write_compressed(File::create(path)?, data)
+}
+
+/// Writes the text content to the writer, compressed with gzip.
+pub fn write_compressed<W: Write, S: AsRef<str>>(writer: W, data: S) -> Result<()> {
+    let mut encoder = GzEncoder::new(writer, Compression::default());
+    encoder.write_all(data.as_ref().as_bytes())?;
+    encoder.finish()?;
+
+    Ok(())
+}
+
+/// Checks if the given data is compressed with gzip.
+///
+/// This checks for the gzip magic number.
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&[0x1f, 0x8b])
+}
+
+/// Decompresses gzip-compressed data.
+pub fn decompress(data: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(data).read_to_end(&mut decompressed)?;
+
+    Ok(decompressed)
+}
+
 /// Reads the given file as a byte array.
 pub fn read_file(path: &PathBuf) -> Result<Vec<u8>> {
     std::fs::read(path).map_err(|e| anyhow!(e))
 }
 
-/// Returns a path to the application's configuration directory.
-pub fn app_config_dir() -> Result<PathBuf> {
-    dirs::config_dir()
+/// The data directory used instead of the default one.
+///
+/// This is set with the `--data-dir` flag.
+static APP_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Uses a different data directory for this run.
+///
+/// This must be called before the data directory is first used.\
+/// The directory is created if it doesn't exist.
+pub fn set_app_data_dir(path: PathBuf) -> Result<()> {
+    std::fs::create_dir_all(&path)?;
+    let path = std::path::absolute(path)?;
+
+    APP_DATA_DIR
+        .set(path)
+        .map_err(|_| anyhow!("The data directory was already set."))
+}
+
+/// Checks if the data directory was overridden for this run.
+///
+/// This is the case with `--data-dir`, or in portable mode.
+pub fn has_custom_data_dir() -> bool {
+    APP_DATA_DIR.get().is_some()
+}
+
+/// The file which enables portable mode when placed beside the executable.
+const PORTABLE_MARKER: &str = "portable";
+
+/// Checks if the launcher was installed as a portable install.
+///
+/// This is the case when the portable marker file is beside the executable.
+pub fn is_portable() -> bool {
+    portable_dir().is_ok_and(|dir| dir.join(PORTABLE_MARKER).is_file())
+}
+
+/// Returns a path to the data directory of a portable install.
+///
+/// This is the `data` directory beside the executable.
+pub fn portable_data_dir() -> Result<PathBuf> {
+    portable_dir().map(|dir| dir.join("data"))
+}
+
+/// Returns the directory containing the executable.
+fn portable_dir() -> Result<PathBuf> {
+    let executable = std::env::current_exe()?;
+    executable
+        .parent()
+        .map(PathBuf::from)
         .ok_or(anyhow!("unknown path"))
-        .map(|dir| dir.join(APP_IDENTIFIER))
 }
 
 /// Returns a path to the application's data directory.
+///
+/// This is the platform's data directory, unless it was overridden\
+/// with `--data-dir`, or the launcher is running in portable mode.
 pub fn app_data_dir() -> Result<PathBuf> {
+    if let Some(path) = APP_DATA_DIR.get() {
+        return Ok(path.clone());
+    }
+
     dirs::data_dir()
         .ok_or(anyhow!("unknown path"))
         .map(|dir| dir.join(APP_IDENTIFIER))
@@ -110,7 +244,280 @@ pub fn get_executable_name<S: AsRef<str>>(path: S) -> String {
         .to_string()
 }
 
+/// Quotes a command-line argument, if needed.
+///
+/// This follows the Windows rules for parsing command-line arguments.\
+/// Arguments without whitespace or quotes are returned as-is.
+pub fn quote_arg<S: AsRef<str>>(arg: S) -> String {
+    let arg = arg.as_ref();
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+    for character in arg.chars() {
+        match character {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote need to be escaped, as well as the quote.
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+
+        if character != '\\' {
+            quoted.push(character);
+        }
+    }
+
+    // Backslashes before the closing quote also need to be escaped.
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+
+    quoted
+}
+
+/// Joins arguments into a command line, quoting them if needed.
+pub fn join_args<S: AsRef<str>>(args: &[S]) -> String {
+    args.iter()
+        .map(quote_arg)
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Splits a command line into its arguments.
+///
+/// This is the reverse of `quote_arg`, and follows the same Windows rules.
+pub fn split_args(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_arg = false;
+    let mut quoted = false;
+    let mut backslashes = 0;
+
+    for character in command_line.chars() {
+        match character {
+            '\\' => {
+                backslashes += 1;
+                in_arg = true;
+            }
+            '"' => {
+                // Backslashes before a quote are escaped, and an odd one escapes the quote.
+                current.push_str(&"\\".repeat(backslashes / 2));
+                if backslashes % 2 == 1 {
+                    current.push('"');
+                } else {
+                    quoted = !quoted;
+                }
+                backslashes = 0;
+                in_arg = true;
+            }
+            ' ' | '\t' if !quoted => {
+                current.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+                if in_arg {
+                    args.push(std::mem::take(&mut current));
+                    in_arg = false;
+                }
+            }
+            _ => {
+                current.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+                current.push(character);
+                in_arg = true;
+            }
+        }
+    }
+
+    current.push_str(&"\\".repeat(backslashes));
+    if in_arg {
+        args.push(current);
+    }
+
+    args
+}
+
 /// Generates a random 16-character ID.
 pub fn random_id() -> String {
     String::from_utf8(rand::rng().sample_iter(&Alphanumeric).take(16).collect()).unwrap()
 }
+
+/// A hash algorithm supported by `hash_file`.
+#[derive(Deserialize, Debug, Default, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    #[default]
+    Sha256,
+    Md5,
+}
+
+/// Computes the hash of a file.
+///
+/// The file is streamed, so large files aren't read into memory.\
+/// The hash is returned as a lowercase hex string.
+pub fn hash_file(path: &PathBuf, algorithm: HashAlgorithm) -> Result<String> {
+    let mut file = File::open(path)?;
+
+    match algorithm {
+        HashAlgorithm::Sha256 => digest_reader::<Sha256>(&mut file),
+        HashAlgorithm::Md5 => digest_reader::<Md5>(&mut file),
+    }
+}
+
+/// Computes the digest of everything read from the reader.
+fn digest_reader<D: Digest + Write>(reader: &mut impl Read) -> Result<String> {
+    let mut hasher = D::new();
+    std::io::copy(reader, &mut hasher)?;
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// Returns the HTTP client shared by all requests.
+pub fn http_client<'a>() -> &'a Client {
+    &HTTP_CLIENT
+}
+
+/// Downloads a file to the given cache path, unless a fresh copy is already cached.
+///
+/// A cached file is fresh if it is younger than `ttl`.\
+/// If `ttl` is `None`, cached files never expire.
+///
+/// Failed downloads are retried, and downloads which don't match the expected size are discarded.\
+/// `on_progress` is called with the received and total byte counts as the download progresses.
+pub async fn cached_download<F: Fn(u64, Option<u64>)>(
+    url: &str,
+    cache_path: &PathBuf,
+    ttl: Option<Duration>,
+    on_progress: F,
+) -> Result<PathBuf> {
+    // Use the cached file if it is still fresh.
+    if is_fresh(cache_path, ttl) {
+        return Ok(cache_path.clone());
+    }
+
+    let mut last_error = anyhow!("no download attempts were made");
+    for attempt in 1..=DOWNLOAD_ATTEMPTS {
+        match download(url, &on_progress).await {
+            Ok(bytes) => {
+                // Write to a temporary file first, so a partial file is never cached.
+                let partial = cache_path.with_extension("part");
+                std::fs::write(&partial, bytes)?;
+                std::fs::rename(&partial, cache_path)?;
+
+                return Ok(cache_path.clone());
+            }
+            Err(error) => {
+                warn!("Download attempt {attempt}/{DOWNLOAD_ATTEMPTS} of '{url}' failed: {error}");
+                last_error = error;
+            }
+        }
+
+        // Wait before trying again.
+        if attempt < DOWNLOAD_ATTEMPTS {
+            tokio::time::sleep(Duration::from_millis(500 * attempt)).await;
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Checks if the file at the given path exists and is younger than `ttl`.
+fn is_fresh(path: &PathBuf, ttl: Option<Duration>) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    let Some(ttl) = ttl else {
+        return true;
+    };
+
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < ttl)
+}
+
+/// Downloads the given URL into memory.
+///
+/// This checks that the downloaded size matches the response's length, if it is known.
+async fn download<F: Fn(u64, Option<u64>)>(url: &str, on_progress: &F) -> Result<Vec<u8>> {
+    let mut response = HTTP_CLIENT.get(url).send().await?.error_for_status()?;
+
+    let total = response.content_length();
+    let mut bytes = Vec::with_capacity(total.unwrap_or_default() as usize);
+
+    // Read the response in chunks.
+    while let Some(chunk) = response.chunk().await? {
+        bytes.extend_from_slice(&chunk);
+        on_progress(bytes.len() as u64, total);
+    }
+
+    check_download(&bytes, total)?;
+
+    Ok(bytes)
+}
+
+/// Checks the integrity of a download.
+///
+/// The download must not be empty, and must match the expected length, if it is known.
+fn check_download(bytes: &[u8], total: Option<u64>) -> Result<()> {
+    if bytes.is_empty() {
+        return Err(anyhow!("the downloaded file is empty"));
+    }
+    if total.is_some_and(|total| total != bytes.len() as u64) {
+        return Err(anyhow!("the downloaded file is incomplete"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_files_are_not_fresh() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("missing.json");
+
+        assert!(!is_fresh(&path, None));
+        assert!(!is_fresh(&path, Some(Duration::from_secs(60))));
+    }
+
+    #[test]
+    fn files_without_ttl_are_always_fresh() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("cached.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        assert!(is_fresh(&path, None));
+        assert!(is_fresh(&path, Some(Duration::from_secs(60))));
+    }
+
+    #[test]
+    fn expired_files_are_not_fresh() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("cached.json");
+        std::fs::write(&path, "{}").unwrap();
+
+        assert!(!is_fresh(&path, Some(Duration::ZERO)));
+    }
+
+    #[test]
+    fn downloads_must_match_their_length() {
+        assert!(check_download(b"data", Some(4)).is_ok());
+        assert!(check_download(b"data", None).is_ok());
+        assert!(check_download(b"dat", Some(4)).is_err());
+        assert!(check_download(b"data!", Some(4)).is_err());
+        assert!(check_download(b"", None).is_err());
+    }
+}
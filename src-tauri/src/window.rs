@@ -1,8 +1,101 @@
-use tauri::AppHandle;
+use crate::app;
+use crate::events;
+use crate::events::Event;
+use crate::utils::MaybeError;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder, Window};
+
+/// Opens the splash screen window.
+///
+/// The main window stays hidden until `finish_startup` is called.
+pub fn open_splash(app_handle: &AppHandle) -> tauri::Result<()> {
+    WebviewWindowBuilder::new(app_handle, "splash", WebviewUrl::App("splash".into()))
+        .title("Yuan Shen Compass")
+        .inner_size(640.0, 360.0)
+        .decorations(false)
+        .resizable(false)
+        .center()
+        .build()?;
+
+    Ok(())
+}
+
+/// Shows the main window once the launcher has started.
+///
+/// The splash screen stays open until `min_duration` has passed since `started`.\
+/// Once the main window is shown, the `ready` event is emitted.
+pub async fn finish_startup(app_handle: AppHandle, started: Instant, min_duration: Duration) {
+    tokio::time::sleep(min_duration.saturating_sub(started.elapsed())).await;
+
+    // Show the main window.
+    if let Some(window) = app_handle.get_webview_window("main") {
+        if let Err(error) = window.show().and_then(|_| window.set_focus()) {
+            warn!("Failed to show the main window: {}", error);
+        }
+    }
+
+    // Close the splash screen, if it was opened.
+    if let Some(window) = app_handle.get_webview_window("splash") {
+        if let Err(error) = window.close() {
+            warn!("Failed to close the splash screen: {}", error);
+        }
+    }
+
+    events::emit_event(&app_handle, Event::AppReady);
+}
 
 /// Closes the application.
+///
+/// If there is work in progress, this fails unless `force` is set.\
+/// The frontend should confirm with the user before forcing.
 #[tauri::command]
-pub fn window__close(app_handle: AppHandle) {
+pub async fn window__close(app_handle: AppHandle, force: Option<bool>) -> MaybeError<()> {
     // TODO: Check configuration state to see if the launcher should minimize to the tray.
+
+    // Check if any work would be lost.
+    if !force.unwrap_or(false) && !app::app__can_close() {
+        return Err("backend.window.error.busy");
+    }
+
+    // Stop the sniffer before exiting.
+    app::sniffer::shutdown_sniffer().await;
+
     app_handle.exit(0);
+
+    Ok(())
+}
+
+/// Minimizes the calling window.
+#[tauri::command]
+pub fn window__minimize(window: Window) -> MaybeError<()> {
+    window.minimize().map_err(|error| {
+        warn!("Failed to minimize window: {}", error);
+        "backend.window.error.minimize"
+    })
+}
+
+/// Maximizes the calling window, or restores it if it is already maximized.
+#[tauri::command]
+pub fn window__toggle_maximize(window: Window) -> MaybeError<()> {
+    let result = match window.is_maximized() {
+        Ok(true) => window.unmaximize(),
+        Ok(false) => window.maximize(),
+        Err(error) => Err(error),
+    };
+
+    result.map_err(|error| {
+        warn!("Failed to toggle window maximization: {}", error);
+        "backend.window.error.maximize"
+    })
+}
+
+/// Starts dragging the calling window.
+///
+/// This should be invoked when the mouse is pressed on a custom titlebar.
+#[tauri::command]
+pub fn window__start_drag(window: Window) -> MaybeError<()> {
+    window.start_dragging().map_err(|error| {
+        warn!("Failed to start dragging window: {}", error);
+        "backend.window.error.drag"
+    })
 }
@@ -8,6 +8,13 @@ class Capabilities {
         await invoke("sniffer__run");
         await invoke("sniffer__open");
     }
+
+    /**
+     * Closes the packet sniffer's window, if it is open.
+     */
+    public static async closeVisualizer(): Promise<void> {
+        await invoke("sniffer__close_visualizer");
+    }
 }
 
 export default Capabilities;
@@ -1,14 +1,55 @@
+import { invoke } from "@tauri-apps/api/core";
+import { join } from "@tauri-apps/api/path";
 import { warn as logWarn } from "@tauri-apps/plugin-log";
 import { Store } from "@tauri-apps/plugin-store";
 
-const cacheStore = await Store.load("cache.json");
+// Keep the cache in the overridden data directory, if there is one.
+const dataDir = await invoke<string | null>("app__data_dir");
+const cacheStore = await Store.load(
+    dataDir ? await join(dataDir, "cache.json") : "cache.json"
+);
 
 class Global {
+    /**
+     * This event is emitted by the Tauri backend once the launcher has started.
+     */
+    public static APP_READY: string = "ysc://app/ready";
+
     /**
      * This event is emitted by the Tauri backend when a packet is received.
      */
     public static VISUALIZER_PACKET: string = "ysc://visualizer/packet";
 
+    /**
+     * This event is emitted by the Tauri backend when the selected profile changes.
+     */
+    public static PROFILE_CHANGED: string = "ysc://profile/changed";
+
+    /**
+     * This event is emitted by the Tauri backend when the game closes.
+     */
+    public static GAME_EXITED: string = "ysc://game/exited";
+
+    /**
+     * This event is emitted by the Tauri backend when the game window opens.
+     */
+    public static GAME_WINDOW_FOUND: string = "ysc://game/window";
+
+    /**
+     * This event is emitted by the Tauri backend while a file is downloading.
+     */
+    public static DOWNLOAD_PROGRESS: string = "ysc://download/progress";
+
+    /**
+     * This event is emitted by the Tauri backend when a message is logged.
+     */
+    public static LOG: string = "ysc://log";
+
+    /**
+     * This event is emitted by the Tauri backend when a new mod file is found.
+     */
+    public static MOD_DISCOVERED: string = "ysc://mods/discovered";
+
     /**
      * Global accessor for the cache store.
      */
@@ -5,6 +5,7 @@ export type ServerAddress = `${string}:${number}`;
  */
 export type Packet = {
     time: number;
+    timestamp?: number; // UNIX timestamp in milliseconds; absent or 0 if unknown.
     source: "client" | "server";
     packetId: number;
     packetName: string;
@@ -15,12 +16,62 @@ export type Packet = {
     binary?: string; // Base64-encoded raw packet data.
 };
 
+/**
+ * Metadata about the session a packet dump was captured in.
+ */
+export type DumpHeader = {
+    profileId: string;
+    profileName: string;
+    version: string;
+};
+
+/**
+ * JSON-serialized packet dump information.
+ */
+export type Dump = {
+    header: DumpHeader | null;
+    packets: Packet[];
+    notes: Record<number, string>; // Notes keyed by packet index.
+    markers: Marker[];
+    skipped: number; // The number of malformed packets which were skipped.
+};
+
+/**
+ * Statistics about the running packet sniffer.
+ */
+export type SnifferStats = {
+    dropped: number; // Packets dropped because the sniffer fell behind.
+    buffered: number;
+};
+
+/**
+ * The result of a short diagnostic capture.
+ */
+export type SeedDiagnostics = {
+    frames: number; // Frames which matched the capture filter.
+    decrypted: number;
+    decoded: number;
+    knownSeeds: number;
+    verdict: "ok" | "no-traffic" | "no-seed" | "undecodable";
+};
+
+/**
+ * A named marker on the capture timeline.
+ */
+export type Marker = {
+    time: number; // Seconds since the capture started.
+    label: string;
+};
+
 /**
  * JSON-serialized version information.
  */
 export type Version = {
     version: string;
     path: string;
+    region?: string;
+    branch?: string;
+    sha256?: string | null;
 };
 
 /**
@@ -43,6 +94,7 @@ export type Mod = {
     path: string;
     version: string;
     tool: Tool;
+    sha256?: string | null;
 };
 
 /**
@@ -55,7 +107,10 @@ export type Profile = {
     version: Version;
     tools: Tool[];
     mods: Mod[];
-    launch_args: string;
+    launch_args: string[];
+    imported?: boolean;
+    wine_prefix?: string | null;
+    wine_dll_overrides?: Record<string, string>;
 };
 
 /**
@@ -65,3 +120,99 @@ export type Colors = {
     dark: boolean;
     primary: string;
 };
+
+/**
+ * The result of importing a shared profile.
+ */
+export type ImportedProfile = {
+    profile: Profile;
+    missing_tools: Tool[];
+    missing_mods: Mod[];
+};
+
+/**
+ * A step of the launch plan, for a single tool or mod.
+ */
+export type LaunchStep = {
+    name: string;
+    path: string;
+    action: "inject" | "open" | "mod" | "skip";
+    error: string | null;
+};
+
+/**
+ * A preview of what launching a profile would do.
+ */
+export type LaunchReport = {
+    command_line: string | null;
+    error: string | null;
+    disable_anti_cheat: boolean;
+    steps: LaunchStep[];
+};
+
+/**
+ * The command the game is opened with.
+ */
+export type LaunchCommand = {
+    executable: string;
+    arguments: string;
+    command_line: string;
+    environment: Record<string, string>;
+};
+
+/**
+ * The path of a profile's tool or mod, and what it resolves to.
+ */
+export type ResolvedPath = {
+    id: string;
+    kind: "tool" | "mod";
+    name: string;
+    path: string;
+    resolved: string | null;
+    exists: boolean;
+    recognized: boolean; // Whether the file is a `.dll` or `.exe`.
+};
+
+/**
+ * A module loaded by the running game.
+ */
+export type GameModule = {
+    name: string;
+    base: number;
+    size: number; // The size of the module in memory, in bytes.
+};
+
+/**
+ * An installed compatibility layer which can run the game.
+ */
+export type Runner = {
+    kind: "wine" | "proton";
+    name: string;
+    path: string;
+};
+
+/**
+ * A Wine prefix known to the launcher.
+ */
+export type WinePrefix = {
+    path: string;
+    valid: boolean;
+};
+
+/**
+ * An event which was emitted by the backend.
+ */
+export type RecordedEvent = {
+    event: string;
+    payload: unknown;
+    timestamp: number;
+};
+
+/**
+ * A translation key which a locale doesn't translate.
+ */
+export type MissingTranslation = {
+    key: string;
+    issue: "missing" | "untranslated";
+    fallback: string; // The fallback text of the key.
+};
@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react";
 
 import type { Packet, ServerAddress } from "@backend/types.ts";
+import { invoke } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import Global from "@backend/Global.ts";
 
@@ -78,9 +79,18 @@ function usePacketList(server: ServerAddress | undefined): PacketHook {
             unlisten = () => ws.close();
         } else {
             // Use a Tauri event listener to receive packets from the backend.
+            // The backend only emits packets while a listener is subscribed.
+            invoke("events__subscribe", { event: Global.VISUALIZER_PACKET });
             listen(Global.VISUALIZER_PACKET, ({ payload }) => {
                 push(payload as Packet);
-            }).then((fn) => (unlisten = fn));
+            }).then((fn) => {
+                unlisten = () => {
+                    fn();
+                    invoke("events__unsubscribe", {
+                        event: Global.VISUALIZER_PACKET
+                    });
+                };
+            });
         }
 
         return () => unlisten();
@@ -7,12 +7,14 @@ import posthog from "posthog-js";
 import Launcher from "@ui/Launcher.tsx";
 import PacketVisualizer from "@ui/PacketVisualizer.tsx";
 import ReplayViewer from "@ui/ReplayViewer.tsx";
+import Splash from "@ui/Splash.tsx";
 
 import "@css/global.scss";
 
 export const router = createBrowserRouter([
     { path: "/*", element: <Launcher /> },
     { path: "/replay", element: <ReplayViewer /> },
+    { path: "/splash", element: <Splash /> },
     { path: "/visualizer", element: <PacketVisualizer /> }
 ]);
 
@@ -22,7 +22,7 @@ import usePacketList from "@hooks/visualizer/usePacketList.ts";
 import useViewport from "@hooks/visualizer/useViewport.ts";
 
 import Global from "@backend/Global.ts";
-import type { Packet as PacketType } from "@backend/types.ts";
+import type { Dump, Packet as PacketType } from "@backend/types.ts";
 
 import "@css/pages/PacketVisualizer.scss";
 
@@ -357,6 +357,7 @@ function PacketVisualizer() {
                     className={"bg-red-800 hover:bg-red-900"}
                     onClick={() => {
                         clear(); // Clear the packet list.
+                        invoke("sniffer__clear_buffer"); // Clear the backend's copy.
                         setSelected(undefined); // Clear the selected packet.
                         setContent(undefined); // Clear the JSON content.
                     }}
@@ -399,7 +400,7 @@ function PacketVisualizer() {
                                     filters: [
                                         {
                                             name: "Packet Dumps",
-                                            extensions: ["json"]
+                                            extensions: ["json", "gz"]
                                         }
                                     ]
                                 });
@@ -410,13 +411,20 @@ function PacketVisualizer() {
                                 }
 
                                 // Pass the file to the backend for processing.
-                                const packets: PacketType[] = await invoke(
+                                const dump: Dump = await invoke(
                                     "sniffer__load",
                                     { filePath: selected }
                                 );
 
                                 // Add the packets to the list.
-                                packets.forEach(push);
+                                dump.packets.forEach(push);
+
+                                // Warn about any packets which couldn't be read.
+                                if (dump.skipped > 0) {
+                                    Global.warn(
+                                        `Skipped ${dump.skipped} malformed packet(s).`
+                                    );
+                                }
                             } catch (error) {
                                 Global.warn("Failed to open file dialog.");
                                 console.warn(error);
@@ -0,0 +1,29 @@
+import { convertFileSrc, invoke } from "@tauri-apps/api/core";
+import { useEffect, useState } from "react";
+
+import "@css/Splash.scss";
+
+/**
+ * This is shown while the launcher starts.
+ * @constructor
+ */
+function Splash() {
+    const [image, setImage] = useState<string | undefined>(undefined);
+
+    useEffect(() => {
+        invoke("appearance__default_splash")
+            .then((path) => setImage(convertFileSrc(path as string)))
+            .catch(() => setImage(undefined));
+    }, []);
+
+    return (
+        <div
+            id={"page__splash"}
+            style={{
+                background: image ? `url(${image})` : undefined
+            }}
+        />
+    );
+}
+
+export default Splash;
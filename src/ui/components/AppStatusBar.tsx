@@ -3,6 +3,8 @@ import { Minus, X } from "lucide-react";
 import { window as viewWindow } from "@tauri-apps/api";
 import { invoke } from "@tauri-apps/api/core";
 
+import { t } from "@backend/Language.ts";
+
 import "@css/components/AppStatusBar.scss";
 
 function AppStatusBar() {
@@ -24,7 +26,14 @@ function AppStatusBar() {
                 <div
                     id={"status-bar__close"}
                     className={"StatusBar__Button"}
-                    onClick={() => invoke("window__close")}
+                    onClick={() =>
+                        invoke("window__close").catch(async (error) => {
+                            // Confirm before discarding in-progress work.
+                            if (confirm(await t(error))) {
+                                await invoke("window__close", { force: true });
+                            }
+                        })
+                    }
                 >
                     <X color={"white"} strokeWidth={2.5} size={18} />
                 </div>
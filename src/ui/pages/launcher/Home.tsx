@@ -10,6 +10,7 @@ import Text from "@components/common/Text.tsx";
 import useColorScheme from "@hooks/appearance/useColorScheme.ts";
 
 import { t } from "@backend/Language.ts";
+import type { Profile } from "@backend/types.ts";
 
 function Home() {
     const colors = useColorScheme();
@@ -34,10 +35,25 @@ function Home() {
                         backgroundColor: colors.primary
                     }}
                     onClick={async () => {
+                        // Suggest launching without mods if the game keeps crashing.
+                        const suggestion = await invoke<string | null>(
+                            "game__safe_mode_suggestion"
+                        );
+                        if (suggestion) {
+                            console.warn(suggestion);
+                        }
+
                         try {
                             await invoke("game__launch");
                         } catch (error) {
-                            console.error(await t(error as string));
+                            const profile = await invoke<Profile | null>(
+                                "profile__get_selected"
+                            );
+                            console.error(
+                                await t(error as string, {
+                                    path: profile?.version.path ?? ""
+                                })
+                            );
                         }
                     }}
                 >